@@ -286,6 +286,66 @@ STATIC mp_obj_t mod_trezorconfig_get(size_t n_args, const mp_obj_t *args) {
 STATIC MP_DEFINE_CONST_FUN_OBJ_VAR_BETWEEN(mod_trezorconfig_get_obj, 2, 3,
                                            mod_trezorconfig_get);
 
+/// def get_length(app: int, key: int, public: bool = False) -> int | None:
+///     """
+///     Gets the length of the value of the given key for the given app (or None
+///     if not set) without reading the value itself.
+///     """
+STATIC mp_obj_t mod_trezorconfig_get_length(size_t n_args,
+                                            const mp_obj_t *args) {
+  uint8_t app = trezor_obj_get_uint8(args[0]);
+  if (app == 0 || app > MAX_APPID) {
+    mp_raise_msg(&mp_type_ValueError, "Invalid app ID.");
+  }
+  uint8_t key = trezor_obj_get_uint8(args[1]);
+  if (n_args > 2 && args[2] == mp_const_true) {
+    app |= FLAG_PUBLIC;
+  }
+  uint16_t appkey = (app << 8) | key;
+  uint16_t len = 0;
+  if (sectrue != storage_get(appkey, NULL, 0, &len)) {
+    return mp_const_none;
+  }
+  return mp_obj_new_int_from_uint(len);
+}
+STATIC MP_DEFINE_CONST_FUN_OBJ_VAR_BETWEEN(mod_trezorconfig_get_length_obj, 2,
+                                           3, mod_trezorconfig_get_length);
+
+/// def get_into(
+///     app: int, key: int, buffer: bytearray, public: bool = False
+/// ) -> int | None:
+///     """
+///     Reads the value of the given key for the given app directly into
+///     `buffer` and returns its length (or None if not set). Raises ValueError
+///     if the buffer is too small.
+///     """
+STATIC mp_obj_t mod_trezorconfig_get_into(size_t n_args, const mp_obj_t *args) {
+  uint8_t app = trezor_obj_get_uint8(args[0]);
+  if (app == 0 || app > MAX_APPID) {
+    mp_raise_msg(&mp_type_ValueError, "Invalid app ID.");
+  }
+  uint8_t key = trezor_obj_get_uint8(args[1]);
+  if (n_args > 3 && args[3] == mp_const_true) {
+    app |= FLAG_PUBLIC;
+  }
+  uint16_t appkey = (app << 8) | key;
+  mp_buffer_info_t buffer;
+  mp_get_buffer_raise(args[2], &buffer, MP_BUFFER_WRITE);
+  uint16_t len = 0;
+  if (sectrue != storage_get(appkey, NULL, 0, &len)) {
+    return mp_const_none;
+  }
+  if (len > buffer.len) {
+    mp_raise_msg(&mp_type_ValueError, "Buffer too small.");
+  }
+  if (len > 0 && sectrue != storage_get(appkey, buffer.buf, len, &len)) {
+    mp_raise_msg(&mp_type_RuntimeError, "Failed to get value from storage.");
+  }
+  return mp_obj_new_int_from_uint(len);
+}
+STATIC MP_DEFINE_CONST_FUN_OBJ_VAR_BETWEEN(mod_trezorconfig_get_into_obj, 3, 4,
+                                           mod_trezorconfig_get_into);
+
 /// def set(app: int, key: int, value: bytes, public: bool = False) -> None:
 ///     """
 ///     Sets a value of given key for given app.
@@ -398,6 +458,51 @@ STATIC mp_obj_t mod_trezorconfig_next_counter(size_t n_args,
 STATIC MP_DEFINE_CONST_FUN_OBJ_VAR_BETWEEN(mod_trezorconfig_next_counter_obj, 2,
                                            3, mod_trezorconfig_next_counter);
 
+/// def get_counter(
+///    app: int, key: int, writable_locked: bool = False,
+/// ) -> int | None:
+///     """
+///     Gets the current value of the counter stored under the given key of the
+///     given app without incrementing it (or None if not set).
+///     """
+STATIC mp_obj_t mod_trezorconfig_get_counter(size_t n_args,
+                                             const mp_obj_t *args) {
+  uint8_t app = trezor_obj_get_uint8(args[0]);
+  if (app == 0 || app > MAX_APPID) {
+    mp_raise_msg(&mp_type_ValueError, "Invalid app ID.");
+  }
+  uint8_t key = trezor_obj_get_uint8(args[1]);
+  if (n_args > 2 && args[2] == mp_const_true) {
+    app |= FLAGS_WRITE;
+  } else {
+    app |= FLAG_PUBLIC;
+  }
+  uint16_t appkey = (app << 8) | key;
+  uint32_t count = 0;
+  if (sectrue != storage_get_counter(appkey, &count)) {
+    return mp_const_none;
+  }
+  return mp_obj_new_int_from_uint(count);
+}
+STATIC MP_DEFINE_CONST_FUN_OBJ_VAR_BETWEEN(mod_trezorconfig_get_counter_obj, 2,
+                                           3, mod_trezorconfig_get_counter);
+
+/// def get_usage() -> tuple[int, int]:
+///     """
+///     Returns the number of used and free bytes in the storage. Items which
+///     were deleted or overwritten count as used until the storage is
+///     compacted.
+///     """
+STATIC mp_obj_t mod_trezorconfig_get_usage(void) {
+  uint32_t used = 0, available = 0;
+  storage_get_usage(&used, &available);
+  mp_obj_t items[2] = {mp_obj_new_int_from_uint(used),
+                       mp_obj_new_int_from_uint(available)};
+  return mp_obj_new_tuple(2, items);
+}
+STATIC MP_DEFINE_CONST_FUN_OBJ_0(mod_trezorconfig_get_usage_obj,
+                                 mod_trezorconfig_get_usage);
+
 /// def wipe() -> None:
 ///     """
 ///     Erases the whole config. Use with caution!
@@ -430,12 +535,19 @@ STATIC const mp_rom_map_elem_t mp_module_trezorconfig_globals_table[] = {
     {MP_ROM_QSTR(MP_QSTR_change_wipe_code),
      MP_ROM_PTR(&mod_trezorconfig_change_wipe_code_obj)},
     {MP_ROM_QSTR(MP_QSTR_get), MP_ROM_PTR(&mod_trezorconfig_get_obj)},
+    {MP_ROM_QSTR(MP_QSTR_get_length),
+     MP_ROM_PTR(&mod_trezorconfig_get_length_obj)},
+    {MP_ROM_QSTR(MP_QSTR_get_into), MP_ROM_PTR(&mod_trezorconfig_get_into_obj)},
     {MP_ROM_QSTR(MP_QSTR_set), MP_ROM_PTR(&mod_trezorconfig_set_obj)},
     {MP_ROM_QSTR(MP_QSTR_delete), MP_ROM_PTR(&mod_trezorconfig_delete_obj)},
     {MP_ROM_QSTR(MP_QSTR_set_counter),
      MP_ROM_PTR(&mod_trezorconfig_set_counter_obj)},
     {MP_ROM_QSTR(MP_QSTR_next_counter),
      MP_ROM_PTR(&mod_trezorconfig_next_counter_obj)},
+    {MP_ROM_QSTR(MP_QSTR_get_counter),
+     MP_ROM_PTR(&mod_trezorconfig_get_counter_obj)},
+    {MP_ROM_QSTR(MP_QSTR_get_usage),
+     MP_ROM_PTR(&mod_trezorconfig_get_usage_obj)},
     {MP_ROM_QSTR(MP_QSTR_wipe), MP_ROM_PTR(&mod_trezorconfig_wipe_obj)},
 };
 STATIC MP_DEFINE_CONST_DICT(mp_module_trezorconfig_globals,
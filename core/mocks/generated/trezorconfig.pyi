@@ -101,6 +101,25 @@ def get(app: int, key: int, public: bool = False) -> bytes | None:
     """
 
 
+# extmod/modtrezorconfig/modtrezorconfig.c
+def get_length(app: int, key: int, public: bool = False) -> int | None:
+    """
+    Gets the length of the value of the given key for the given app (or None
+    if not set) without reading the value itself.
+    """
+
+
+# extmod/modtrezorconfig/modtrezorconfig.c
+def get_into(
+    app: int, key: int, buffer: bytearray, public: bool = False
+) -> int | None:
+    """
+    Reads the value of the given key for the given app directly into
+    `buffer` and returns its length (or None if not set). Raises ValueError
+    if the buffer is too small.
+    """
+
+
 # extmod/modtrezorconfig/modtrezorconfig.c
 def set(app: int, key: int, value: bytes, public: bool = False) -> None:
     """
@@ -136,6 +155,25 @@ def next_counter(
     """
 
 
+# extmod/modtrezorconfig/modtrezorconfig.c
+def get_counter(
+   app: int, key: int, writable_locked: bool = False,
+) -> int | None:
+    """
+    Gets the current value of the counter stored under the given key of the
+    given app without incrementing it (or None if not set).
+    """
+
+
+# extmod/modtrezorconfig/modtrezorconfig.c
+def get_usage() -> tuple[int, int]:
+    """
+    Returns the number of used and free bytes in the storage. Items which
+    were deleted or overwritten count as used until the storage is
+    compacted.
+    """
+
+
 # extmod/modtrezorconfig/modtrezorconfig.c
 def wipe() -> None:
     """
@@ -3,7 +3,7 @@ from trezor import ui, utils, workflow
 from trezor.enums import BackupType
 
 
-def get() -> tuple[bytes | None, BackupType]:
+def get() -> tuple[bytes | None, BackupType | None]:
     return get_secret(), get_type()
 
 
@@ -11,14 +11,14 @@ def get_secret() -> bytes | None:
     return storage.device.get_mnemonic_secret()
 
 
-def get_type() -> BackupType:
+def get_type() -> BackupType | None:
     return storage.device.get_backup_type()
 
 
 def is_bip39() -> bool:
     """
-    If False then SLIP-39 (either Basic or Advanced).
-    Other invalid values are checked directly in storage.
+    If False then SLIP-39 (either Basic or Advanced), or the device is not
+    initialized. Other invalid values are checked directly in storage.
     """
     return get_type() == BackupType.Bip39
 
@@ -1,7 +1,11 @@
 import storage.cache
 import storage.device
 from storage.cache import APP_COMMON_SAFETY_CHECKS_TEMPORARY
-from storage.device import SAFETY_CHECK_LEVEL_PROMPT, SAFETY_CHECK_LEVEL_STRICT
+from storage.device import (
+    SAFETY_CHECK_LEVEL_PROMPT,
+    SAFETY_CHECK_LEVEL_STRICT,
+    SAFETY_CHECK_SOURCE_HOST,
+)
 from trezor.enums import SafetyCheckLevel
 
 
@@ -13,10 +17,9 @@ def read_setting() -> SafetyCheckLevel:
     if temporary_safety_check_level:
         return int.from_bytes(temporary_safety_check_level, "big")  # type: ignore [int-into-enum]
     else:
-        stored = storage.device.safety_check_level()
-        if stored == SAFETY_CHECK_LEVEL_STRICT:
+        if storage.device.is_safety_check_strict():
             return SafetyCheckLevel.Strict
-        elif stored == SAFETY_CHECK_LEVEL_PROMPT:
+        elif storage.device.is_safety_check_prompt():
             return SafetyCheckLevel.PromptAlways
         else:
             raise ValueError("Unknown SafetyCheckLevel")
@@ -28,12 +31,18 @@ def apply_setting(level: SafetyCheckLevel) -> None:
     """
     if level == SafetyCheckLevel.Strict:
         storage.cache.delete(APP_COMMON_SAFETY_CHECKS_TEMPORARY)
-        storage.device.set_safety_check_level(SAFETY_CHECK_LEVEL_STRICT)
+        storage.device.set_safety_check_level(
+            SAFETY_CHECK_LEVEL_STRICT, SAFETY_CHECK_SOURCE_HOST
+        )
     elif level == SafetyCheckLevel.PromptAlways:
         storage.cache.delete(APP_COMMON_SAFETY_CHECKS_TEMPORARY)
-        storage.device.set_safety_check_level(SAFETY_CHECK_LEVEL_PROMPT)
+        storage.device.set_safety_check_level(
+            SAFETY_CHECK_LEVEL_PROMPT, SAFETY_CHECK_SOURCE_HOST
+        )
     elif level == SafetyCheckLevel.PromptTemporarily:
-        storage.device.set_safety_check_level(SAFETY_CHECK_LEVEL_STRICT)
+        storage.device.set_safety_check_level(
+            SAFETY_CHECK_LEVEL_STRICT, SAFETY_CHECK_SOURCE_HOST
+        )
         storage.cache.set(APP_COMMON_SAFETY_CHECKS_TEMPORARY, level.to_bytes(1, "big"))
     else:
         raise ValueError("Unknown SafetyCheckLevel")
@@ -33,17 +33,18 @@ async def load_device(ctx: wire.Context, msg: LoadDevice) -> Success:
         else:
             raise wire.ProcessError("Invalid group count")
 
-        storage.device.set_slip39_identifier(identifier)
-        storage.device.set_slip39_iteration_exponent(iteration_exponent)
-
     storage.device.store_mnemonic_secret(
         secret,
         backup_type,
         needs_backup=msg.needs_backup is True,
         no_backup=msg.no_backup is True,
     )
+    if is_slip39:
+        storage.device.set_slip39_identifier(identifier)
+        storage.device.set_slip39_iteration_exponent(iteration_exponent)
     storage.device.set_passphrase_enabled(bool(msg.passphrase_protection))
     storage.device.set_label(msg.label or "")
+    storage.device.finalize_initialization()
     if msg.pin:
         config.change_pin("", msg.pin, None, None)
 
@@ -57,6 +58,15 @@ def _validate(msg: LoadDevice) -> int:
     if not msg.mnemonics:
         raise wire.ProcessError("No mnemonic provided")
 
+    if msg.needs_backup and msg.no_backup:
+        raise wire.DataError("needs_backup and no_backup are mutually exclusive")
+
+    if msg.label is not None:
+        try:
+            msg.label = storage.device.normalize_label(msg.label)
+        except ValueError as e:
+            raise wire.DataError(str(e))
+
     word_count = len(msg.mnemonics[0].split(" "))
     for m in msg.mnemonics[1:]:
         if word_count != len(m.split(" ")):
@@ -64,6 +74,14 @@ def _validate(msg: LoadDevice) -> int:
                 "All shares are required to have the same number of words"
             )
 
+    if not backup_types.is_slip39_word_count(word_count):
+        try:
+            storage.device.check_mnemonic_secret(
+                msg.mnemonics[0].encode(), BackupType.Bip39
+            )
+        except ValueError as e:
+            raise wire.DataError(str(e))
+
     return word_count
 
 
@@ -18,9 +18,10 @@ def validate_homescreen(homescreen: bytes) -> None:
     if homescreen == b"":
         return
 
-    if len(homescreen) > storage.device.HOMESCREEN_MAXSIZE:
+    maxsize = storage.device.get_homescreen_maxsize()
+    if len(homescreen) > maxsize:
         raise wire.DataError(
-            f"Homescreen is too large, maximum size is {storage.device.HOMESCREEN_MAXSIZE} bytes"
+            f"Homescreen is too large, maximum size is {maxsize} bytes"
         )
 
     try:
@@ -57,10 +58,12 @@ async def apply_settings(ctx: wire.Context, msg: ApplySettings) -> Success:
             raise wire.DataError("Invalid homescreen")
 
     if msg.label is not None:
-        if len(msg.label) > storage.device.LABEL_MAXLENGTH:
-            raise wire.DataError("Label too long")
-        await require_confirm_change_label(ctx, msg.label)
-        storage.device.set_label(msg.label)
+        try:
+            label = storage.device.normalize_label(msg.label)
+        except ValueError as e:
+            raise wire.DataError(str(e))
+        await require_confirm_change_label(ctx, label)
+        storage.device.set_label(label)
 
     if msg.use_passphrase is not None:
         await require_confirm_change_passphrase(ctx, msg.use_passphrase)
@@ -87,6 +90,8 @@ async def apply_settings(ctx: wire.Context, msg: ApplySettings) -> Success:
         safety_checks.apply_setting(msg.safety_checks)
 
     if msg.display_rotation is not None:
+        if storage.device.is_rotation_locked():
+            raise wire.ProcessError("Display rotation is locked")
         await require_confirm_change_display_rotation(ctx, msg.display_rotation)
         storage.device.set_rotation(msg.display_rotation)
 
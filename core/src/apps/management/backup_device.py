@@ -20,7 +20,7 @@ async def backup_device(ctx: wire.Context, msg: BackupDevice) -> Success:
         raise wire.ProcessError("Seed already backed up")
 
     mnemonic_secret, mnemonic_type = mnemonic.get()
-    if mnemonic_secret is None:
+    if mnemonic_secret is None or mnemonic_type is None:
         raise RuntimeError
 
     storage.device.set_unfinished_backup(True)
@@ -75,6 +75,12 @@ def _validate(msg: RecoveryDevice) -> None:
     if msg.dry_run and not storage.device.is_initialized():
         raise wire.NotInitialized("Device is not initialized")
 
+    if msg.label is not None:
+        try:
+            msg.label = storage.device.normalize_label(msg.label)
+        except ValueError as e:
+            raise wire.DataError(str(e))
+
     if msg.enforce_wordlist is False:
         raise wire.ProcessError(
             "Value enforce_wordlist must be True, Trezor Core enforces words automatically."
@@ -129,6 +129,12 @@ async def _finish_recovery(
     if backup_type is None:
         raise RuntimeError
 
+    if backup_type in (BackupType.Slip39_Basic, BackupType.Slip39_Advanced):
+        try:
+            storage.recovery.verify_before_commit()
+        except ValueError as e:
+            raise wire.ProcessError(str(e))
+
     storage.device.store_mnemonic_secret(
         secret, backup_type, needs_backup=False, no_backup=False
     )
@@ -140,6 +146,7 @@ async def _finish_recovery(
             raise RuntimeError
         storage.device.set_slip39_identifier(identifier)
         storage.device.set_slip39_iteration_exponent(exponent)
+    storage.device.finalize_initialization()
 
     storage.recovery.end_progress()
 
@@ -31,6 +31,8 @@ def process_slip39(words: str) -> tuple[bytes | None, slip39.Share]:
     (or None if more shares are needed) and the share's group index and member index.
     """
     share = slip39.decode_mnemonic(words)
+    # a dry run checks the backup of an already initialized device
+    dry_run = storage.recovery.is_dry_run()
 
     remaining = storage.recovery.fetch_slip39_remaining_shares()
 
@@ -42,7 +44,7 @@ def process_slip39(words: str) -> tuple[bytes | None, slip39.Share]:
         storage.recovery.set_slip39_remaining_shares(
             share.threshold - 1, share.group_index
         )
-        storage.recovery_shares.set(share.index, share.group_index, words)
+        storage.recovery_shares.set(share.index, share.group_index, words, dry_run)
 
         # if share threshold and group threshold are 1
         # we can calculate the secret right away
@@ -71,7 +73,7 @@ def process_slip39(words: str) -> tuple[bytes | None, slip39.Share]:
         remaining_for_share - 1, share.group_index
     )
     remaining[share.group_index] = remaining_for_share - 1
-    storage.recovery_shares.set(share.index, share.group_index, words)
+    storage.recovery_shares.set(share.index, share.group_index, words, dry_run)
 
     if remaining.count(0) < share.group_threshold:
         # we need more shares
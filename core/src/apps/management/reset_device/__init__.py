@@ -89,9 +89,10 @@ async def reset_device(ctx: wire.Context, msg: ResetDevice) -> Success:
     storage.device.store_mnemonic_secret(
         secret,  # for SLIP-39, this is the EMS
         msg.backup_type,
-        needs_backup=not perform_backup,
+        needs_backup=not perform_backup and not msg.no_backup,
         no_backup=bool(msg.no_backup),
     )
+    storage.device.finalize_initialization()
 
     # if we backed up the wallet, show success message
     if perform_backup:
@@ -187,6 +188,11 @@ def _validate_reset_device(msg: ResetDevice) -> None:
         raise wire.ProcessError("Can't show internal entropy when backup is skipped")
     if storage.device.is_initialized():
         raise wire.UnexpectedMessage("Already initialized")
+    if msg.label is not None:
+        try:
+            msg.label = storage.device.normalize_label(msg.label)
+        except ValueError as e:
+            raise wire.DataError(str(e))
 
 
 def _compute_secret_from_entropy(
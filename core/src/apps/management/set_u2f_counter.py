@@ -21,6 +21,6 @@ async def set_u2f_counter(ctx: wire.Context, msg: SetU2FCounter) -> Success:
         br_code=ButtonRequestType.ProtectCall,
     )
 
-    storage.device.set_u2f_counter(msg.u2f_counter)
+    storage.device.set_u2f_counter(msg.u2f_counter, force=True)
 
     return Success(message="U2F counter set")
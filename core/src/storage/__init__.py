@@ -1,6 +1,11 @@
+from typing import TYPE_CHECKING
+
 from storage import cache, common, device
 from trezor import config
 
+if TYPE_CHECKING:
+    from typing import Any
+
 
 def set_current_version() -> None:
     device.set_version(common.STORAGE_VERSION_CURRENT)
@@ -9,6 +14,7 @@ def set_current_version() -> None:
 def wipe() -> None:
     config.wipe()
     cache.clear_all()
+    common.invalidate_cache()
 
 
 def init_unlocked() -> None:
@@ -22,6 +28,26 @@ def init_unlocked() -> None:
     if device.is_version_stored() and not device.is_initialized():
         common.set_bool(common.APP_DEVICE, device.INITIALIZED, True, public=True)
 
+    device.snapshot_flags_at_boot()
+
+
+def migrate_dry_run() -> dict[str, Any]:
+    """
+    Describes the changes `init_unlocked()` would make to the stored fields,
+    without making them.
+    """
+    version = device.get_version()
+    changes = []
+    if version == common.STORAGE_VERSION_01:
+        counter = common.get(common.APP_DEVICE, device.U2F_COUNTER)
+        if counter is not None:
+            count = int.from_bytes(counter, "big")
+            changes.append(f"U2F_COUNTER: move {count} to the public counter")
+        changes.append(f"VERSION: 1 -> {common.STORAGE_VERSION_CURRENT[0]}")
+    if device.is_version_stored() and not device.is_initialized():
+        changes.append("INITIALIZED: set")
+    return {"version": version, "changes": changes}
+
 
 def reset() -> None:
     """
@@ -32,6 +58,16 @@ def reset() -> None:
     common.set(common.APP_DEVICE, device.DEVICE_ID, device_id.encode(), public=True)
 
 
+def get_storage_stats() -> dict[str, int]:
+    """
+    Best-effort usage statistics in bytes. Items which were deleted or overwritten
+    are counted as used until the storage gets compacted, so `used` may be
+    overestimated and `available` underestimated.
+    """
+    used, available = config.get_usage()
+    return {"used": used, "available": available}
+
+
 def _migrate_from_version_01() -> None:
     # Make the U2F counter public and writable even when storage is locked.
     # U2F counter wasn't public, so we are intentionally not using storage.device module.
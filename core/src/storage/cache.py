@@ -30,6 +30,7 @@ APP_COMMON_SEED_WITHOUT_PASSPHRASE = 0 | _SESSIONLESS_FLAG
 APP_COMMON_SAFETY_CHECKS_TEMPORARY = 1 | _SESSIONLESS_FLAG
 STORAGE_DEVICE_EXPERIMENTAL_FEATURES = 2 | _SESSIONLESS_FLAG
 APP_COMMON_REQUEST_PIN_LAST_UNLOCK = 3 | _SESSIONLESS_FLAG
+STORAGE_DEVICE_FLAGS_AT_BOOT = 4 | _SESSIONLESS_FLAG
 
 
 # === Homescreen storage ===
@@ -132,6 +133,7 @@ class SessionlessCache(DataCache):
             1,  # APP_COMMON_SAFETY_CHECKS_TEMPORARY
             1,  # STORAGE_DEVICE_EXPERIMENTAL_FEATURES
             4,  # APP_COMMON_REQUEST_PIN_LAST_UNLOCK
+            4,  # STORAGE_DEVICE_FLAGS_AT_BOOT
         )
         super().__init__()
 
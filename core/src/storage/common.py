@@ -13,21 +13,83 @@ APP_WEBAUTHN           = const(0x04)
 _FALSE_BYTE = b"\x00"
 _TRUE_BYTE = b"\x01"
 
+# Integers are stored big-endian, which host-side tooling reading raw values
+# relies on. Counters are the exception, their format is private to the storage.
+
 STORAGE_VERSION_01 = b"\x01"
 STORAGE_VERSION_CURRENT = b"\x02"
 
+# Largest value accepted by set_raw(), matches the homescreen field.
+_RAW_MAXSIZE = const(16384)
+
+# Opt-in read cache of public values, see enable_cache().
+_cache: dict[tuple[int, int], bytes | None] | None = None
+
+# Incremented on every write, see get_storage_generation().
+_generation = 0
+
+
+def enable_cache(enable: bool) -> None:
+    """
+    Memoizes reads of public values until the next write or `invalidate_cache()`
+    call. Private values are never cached, so that no secret stays in RAM after
+    the storage is locked.
+    """
+    global _cache
+    _cache = {} if enable else None
+
+
+def invalidate_cache() -> None:
+    if _cache is not None:
+        _cache.clear()
+
+
+def get_storage_generation() -> int:
+    """
+    Returns a number that grows with every write since boot. Comparing two
+    values tells whether the storage was modified in between.
+    """
+    return _generation
+
+
+def _on_write() -> None:
+    global _generation
+    _generation += 1
+    invalidate_cache()
+
 
 def set(app: int, key: int, data: bytes, public: bool = False) -> None:
+    _on_write()
     config.set(app, key, data, public)
 
 
+def set_if_changed(app: int, key: int, data: bytes, public: bool = False) -> None:
+    """Like set(), but skips the flash write if the value is already stored."""
+    if get(app, key, public) != data:
+        set(app, key, data, public)
+
+
 def get(app: int, key: int, public: bool = False) -> bytes | None:
-    return config.get(app, key, public)
+    if _cache is None or not public:
+        return config.get(app, key, public)
+    cache_key = (app, key)
+    if cache_key not in _cache:
+        _cache[cache_key] = config.get(app, key, public)
+    return _cache[cache_key]
+
+
+def get_length(app: int, key: int, public: bool = False) -> int | None:
+    return config.get_length(app, key, public)
+
+
+def get_into(app: int, key: int, buffer: bytearray, public: bool = False) -> int | None:
+    return config.get_into(app, key, buffer, public)
 
 
 def delete(
     app: int, key: int, public: bool = False, writable_locked: bool = False
 ) -> None:
+    _on_write()
     config.delete(app, key, public, writable_locked)
 
 
@@ -40,9 +102,9 @@ def set_true_or_delete(app: int, key: int, value: bool) -> None:
 
 def set_bool(app: int, key: int, value: bool, public: bool = False) -> None:
     if value:
-        set(app, key, _TRUE_BYTE, public)
+        set_if_changed(app, key, _TRUE_BYTE, public)
     else:
-        set(app, key, _FALSE_BYTE, public)
+        set_if_changed(app, key, _FALSE_BYTE, public)
 
 
 def get_bool(app: int, key: int, public: bool = False) -> bool:
@@ -50,7 +112,7 @@ def get_bool(app: int, key: int, public: bool = False) -> bool:
 
 
 def set_uint8(app: int, key: int, val: int) -> None:
-    set(app, key, val.to_bytes(1, "big"))
+    set_if_changed(app, key, val.to_bytes(1, "big"))
 
 
 def get_uint8(app: int, key: int) -> int | None:
@@ -61,7 +123,7 @@ def get_uint8(app: int, key: int) -> int | None:
 
 
 def set_uint16(app: int, key: int, val: int) -> None:
-    set(app, key, val.to_bytes(2, "big"))
+    set_if_changed(app, key, val.to_bytes(2, "big"))
 
 
 def get_uint16(app: int, key: int) -> int | None:
@@ -72,8 +134,27 @@ def get_uint16(app: int, key: int) -> int | None:
 
 
 def next_counter(app: int, key: int, writable_locked: bool = False) -> int:
+    _on_write()
     return config.next_counter(app, key, writable_locked)
 
 
+def get_counter(app: int, key: int, writable_locked: bool = False) -> int | None:
+    return config.get_counter(app, key, writable_locked)
+
+
 def set_counter(app: int, key: int, count: int, writable_locked: bool = False) -> None:
+    _on_write()
     config.set_counter(app, key, count, writable_locked)
+
+
+if __debug__:
+
+    def get_raw(app: int, key: int, public: bool = False) -> bytes | None:
+        """Reads any field by app/key. Only available in debug builds."""
+        return get(app, key, public)
+
+    def set_raw(app: int, key: int, value: bytes, public: bool = False) -> None:
+        """Writes any field by app/key. Only available in debug builds."""
+        if len(value) > _RAW_MAXSIZE:
+            raise ValueError  # value too large
+        set(app, key, value, public)
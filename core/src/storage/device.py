@@ -1,17 +1,23 @@
 from micropython import const
 from typing import TYPE_CHECKING
-from ubinascii import hexlify
+from ubinascii import hexlify, unhexlify
 
 import storage.cache
 from storage import common
 
 if TYPE_CHECKING:
     from trezor.enums import BackupType
+    from typing import Any, Callable
     from typing_extensions import Literal
 
 # Namespace:
 _NAMESPACE = common.APP_DEVICE
 
+# Getters of fields without a natural default return None when the field is unset.
+# Getters of fields with a default (rotation, flags, autolock delay, backup type,
+# safety check level, brightness) return the default instead, and each has
+# a `*_raw` variant which returns the stored value as is, or None when unset.
+
 # fmt: off
 # Keys:
 DEVICE_ID                  = const(0x00)  # bytes
@@ -36,16 +42,36 @@ _SD_SALT_AUTH_KEY          = const(0x12)  # bytes
 INITIALIZED                = const(0x13)  # bool (0x01 or empty)
 _SAFETY_CHECK_LEVEL        = const(0x14)  # int
 _EXPERIMENTAL_FEATURES     = const(0x15)  # bool (0x01 or empty)
+_SLIP39_GROUP_THRESHOLD    = const(0x16)  # int
+_SLIP39_GROUP_COUNT        = const(0x17)  # int
+_LABEL_COLOR               = const(0x18)  # int
+_BRIGHTNESS                = const(0x19)  # int
+_SAFETY_CHECK_LEVEL_SOURCE = const(0x1B)  # int
+_STORAGE_SALT              = const(0x1C)  # bytes
+_ROTATION_LOCKED           = const(0x1D)  # bool (0x01 or empty)
 
 SAFETY_CHECK_LEVEL_STRICT  : Literal[0] = const(0)
 SAFETY_CHECK_LEVEL_PROMPT  : Literal[1] = const(1)
 _DEFAULT_SAFETY_CHECK_LEVEL = SAFETY_CHECK_LEVEL_STRICT
 if TYPE_CHECKING:
     StorageSafetyCheckLevel = Literal[0, 1]
+
+# Who last changed the safety check level, see get_safety_check_level_source().
+SAFETY_CHECK_SOURCE_HOST   = const(1)
+SAFETY_CHECK_SOURCE_UNSET  = const(0xFF)
 # fmt: on
 
 HOMESCREEN_MAXSIZE = 16384
+
+# Formats accepted by set_homescreen(), along with their magic bytes.
+HOMESCREEN_FORMAT_TOIF = const(0)
+HOMESCREEN_FORMAT_JPEG = const(1)
+_HOMESCREEN_MAGIC = {
+    HOMESCREEN_FORMAT_TOIF: (b"TOIf", b"TOIg"),
+    HOMESCREEN_FORMAT_JPEG: (b"\xff\xd8\xff",),
+}
 LABEL_MAXLENGTH = 32
+BRIGHTNESS_DEFAULT = 255  # full brightness
 
 if __debug__:
     AUTOLOCK_DELAY_MINIMUM = 10 * 1000  # 10 seconds
@@ -55,27 +81,164 @@ AUTOLOCK_DELAY_DEFAULT = 10 * 60 * 1000  # 10 minutes
 # autolock intervals larger than AUTOLOCK_DELAY_MAXIMUM cause issues in the scheduler
 AUTOLOCK_DELAY_MAXIMUM = 0x2000_0000  # ~6 days
 
+# Device flags are opaque to the firmware, their meaning is assigned by host
+# software through ApplyFlags. Only the lower 16 bits are allocated for that,
+# the upper 16 bits are reserved. As the firmware does not know what the bits
+# mean, get_flags_decoded() names them by position: "flag_0" to "flag_15".
+FLAGS_KNOWN_MASK = 0x0000_FFFF
+
+# Fields that are only meaningful with experimental features enabled, as
+# (key, public) pairs. They are deleted when experimental features are disabled.
+_EXPERIMENTAL_ONLY_FIELDS: tuple[tuple[int, bool], ...] = ()
+
+# Device ID is 12 random bytes, hex-encoded.
+_DEVICE_ID_LENGTH = const(24)
+_DEVICE_ID_WRITE_ATTEMPTS = const(3)
+
+# Per-device salt generated on first use, see get_storage_salt().
+_STORAGE_SALT_LENGTH = const(32)
+
+# Accepted mnemonic secret lengths in bytes, see check_mnemonic_secret().
+# BIP-39 stores the mnemonic itself, 12 words of at least 3 letters up to 24 words
+# of at most 8 letters, separated by spaces.
+_BIP39_SECRET_MIN_LENGTH = const(47)
+_BIP39_SECRET_MAX_LENGTH = const(215)
+# SLIP-39 stores the encrypted master secret, 128 to 256 bits in 16-bit steps.
+_SLIP39_SECRET_MIN_LENGTH = const(16)
+_SLIP39_SECRET_MAX_LENGTH = const(32)
+
+# SLIP-39 shares encode the iteration exponent in 5 bits.
+_SLIP39_ITERATION_EXPONENT_MAX = const(31)
+
 # Length of SD salt auth tag.
 # Other SD-salt-related constants are in sd_salt.py
 SD_SALT_AUTH_KEY_LEN_BYTES = const(16)
 
+# fmt: off
+# Field metadata: name -> (key, public, max size in bytes or None if unbounded, type)
+# Every key must be listed here, the unit tests check that no two fields collide.
+_FIELDS = {
+    "DEVICE_ID":                   (DEVICE_ID, True, _DEVICE_ID_LENGTH, "str"),
+    "VERSION":                     (_VERSION, False, 1, "int"),
+    "MNEMONIC_SECRET":             (_MNEMONIC_SECRET, False, None, "bytes"),
+    "LANGUAGE":                    (_LANGUAGE, False, None, "str"),
+    "LABEL":                       (_LABEL, True, LABEL_MAXLENGTH, "str"),
+    "USE_PASSPHRASE":              (_USE_PASSPHRASE, False, 1, "bool"),
+    "HOMESCREEN":                  (_HOMESCREEN, True, HOMESCREEN_MAXSIZE, "bytes"),
+    "NEEDS_BACKUP":                (_NEEDS_BACKUP, False, 1, "bool"),
+    "FLAGS":                       (_FLAGS, False, 4, "int"),
+    "U2F_COUNTER":                 (U2F_COUNTER, True, None, "int"),
+    "PASSPHRASE_ALWAYS_ON_DEVICE": (_PASSPHRASE_ALWAYS_ON_DEVICE, False, 1, "bool"),
+    "UNFINISHED_BACKUP":           (_UNFINISHED_BACKUP, False, 1, "bool"),
+    "AUTOLOCK_DELAY_MS":           (_AUTOLOCK_DELAY_MS, False, 4, "int"),
+    "NO_BACKUP":                   (_NO_BACKUP, False, 1, "bool"),
+    "BACKUP_TYPE":                 (_BACKUP_TYPE, False, 1, "int"),
+    "ROTATION":                    (_ROTATION, True, 2, "int"),
+    "SLIP39_IDENTIFIER":           (_SLIP39_IDENTIFIER, False, 2, "int"),
+    "SLIP39_ITERATION_EXPONENT":   (_SLIP39_ITERATION_EXPONENT, False, 1, "int"),
+    "SD_SALT_AUTH_KEY":            (_SD_SALT_AUTH_KEY, True, SD_SALT_AUTH_KEY_LEN_BYTES, "bytes"),
+    "INITIALIZED":                 (INITIALIZED, True, 1, "bool"),
+    "SAFETY_CHECK_LEVEL":          (_SAFETY_CHECK_LEVEL, False, 1, "int"),
+    "EXPERIMENTAL_FEATURES":       (_EXPERIMENTAL_FEATURES, False, 1, "bool"),
+    "SLIP39_GROUP_THRESHOLD":      (_SLIP39_GROUP_THRESHOLD, False, 1, "int"),
+    "SLIP39_GROUP_COUNT":          (_SLIP39_GROUP_COUNT, False, 1, "int"),
+    "LABEL_COLOR":                 (_LABEL_COLOR, True, 4, "int"),
+    "BRIGHTNESS":                  (_BRIGHTNESS, True, 1, "int"),
+    "SAFETY_CHECK_LEVEL_SOURCE":   (_SAFETY_CHECK_LEVEL_SOURCE, False, 1, "int"),
+    "STORAGE_SALT":                (_STORAGE_SALT, False, _STORAGE_SALT_LENGTH, "bytes"),
+    "ROTATION_LOCKED":             (_ROTATION_LOCKED, False, 1, "bool"),
+}
+# fmt: on
+
+_autolock_delay_observer: Callable[[int], None] | None = None
+_rotation_observer: Callable[[int], None] | None = None
+
+# Limit enforced by set_homescreen(), see set_homescreen_maxsize().
+_homescreen_maxsize = HOMESCREEN_MAXSIZE
+
+
+def _check_exact_length(name: str, value: bytes | str) -> None:
+    """For fields of a fixed size, which is listed as their max_size."""
+    expected = _FIELDS[name][2]
+    if len(value) != expected:
+        raise ValueError(f"Invalid {name} length, expected {expected}")
+
+
+def _check_int(name: str, value: Any) -> None:
+    """Setters of integer fields check the type first, to name the field."""
+    if not isinstance(value, int):
+        raise TypeError(f"Invalid {name} type, expected int")
+
 
 def is_version_stored() -> bool:
     return bool(common.get(_NAMESPACE, _VERSION))
 
 
 def get_version() -> bytes | None:
+    """
+    Returns the stored version, or None. A single read, so there is no need to
+    call is_version_stored() first.
+    """
     return common.get(_NAMESPACE, _VERSION)
 
 
-def set_version(version: bytes) -> None:
-    common.set(_NAMESPACE, _VERSION, version)
+def get_version_int() -> int | None:
+    version = get_version()
+    if version is None:
+        return None
+    if len(version) != 1:
+        raise ValueError("Version must be a single byte")
+    return version[0]
+
+
+def set_version(version: bytes, force: bool = False) -> None:
+    """
+    Only the known storage versions are accepted, unless `force` is set. The
+    version is always a single byte.
+    """
+    if len(version) != 1:
+        raise ValueError("Version must be a single byte")
+    if not force and version not in (
+        common.STORAGE_VERSION_01,
+        common.STORAGE_VERSION_CURRENT,
+    ):
+        raise ValueError("Unknown storage version")
+    common.set_if_changed(_NAMESPACE, _VERSION, version)
+
+
+def check_version_compatibility() -> bool:
+    """
+    Returns False if the storage was written by a newer firmware, e.g. after a
+    downgrade, in which case fields might be misinterpreted.
+    """
+    version = get_version()
+    if not version:
+        return True
+    return version <= common.STORAGE_VERSION_CURRENT
 
 
 def is_initialized() -> bool:
     return common.get_bool(_NAMESPACE, INITIALIZED, public=True)
 
 
+def is_fresh_device() -> bool:
+    """
+    True for a device with neither a storage version nor the initialized flag,
+    i.e. one which was never set up or was wiped since.
+    """
+    return not is_version_stored() and not is_initialized()
+
+
+def require_initialized() -> None:
+    """
+    Raises RuntimeError if the device is not initialized. The SLIP-39 getters
+    (identifier, iteration exponent, group threshold and count) call this when
+    passed `require_init=True`.
+    """
+    if not is_initialized():
+        raise RuntimeError  # device is not initialized
+
+
 def _new_device_id() -> str:
     from trezorcrypto import random  # avoid pulling in trezor.crypto
 
@@ -83,24 +246,115 @@ def _new_device_id() -> str:
 
 
 def get_device_id() -> str:
+    """
+    Generates and stores the device ID on first access. A failed write is retried
+    a few times, and raises RuntimeError if it keeps failing, so that an ID which
+    was not persisted is never returned.
+    """
     dev_id = common.get(_NAMESPACE, DEVICE_ID, public=True)
     if not dev_id:
         dev_id = _new_device_id().encode()
-        common.set(_NAMESPACE, DEVICE_ID, dev_id, public=True)
+        for attempt in range(_DEVICE_ID_WRITE_ATTEMPTS):
+            try:
+                common.set(_NAMESPACE, DEVICE_ID, dev_id, public=True)
+                break
+            except RuntimeError:
+                if attempt == _DEVICE_ID_WRITE_ATTEMPTS - 1:
+                    raise
+    return dev_id.decode()
+
+
+def get_storage_salt() -> bytes:
+    """
+    Returns a random per-device salt, generating and storing it on first access.
+    Requires the storage to be unlocked.
+    """
+    salt = common.get(_NAMESPACE, _STORAGE_SALT)
+    if not salt:
+        from trezorcrypto import random  # avoid pulling in trezor.crypto
+
+        salt = random.bytes(_STORAGE_SALT_LENGTH)
+        common.set(_NAMESPACE, _STORAGE_SALT, salt)
+    return salt
+
+
+def get_device_id_bytes() -> bytes:
+    """The device ID decoded to its 12 raw bytes."""
+    device_id = get_device_id()
+    _check_device_id(device_id)
+    return unhexlify(device_id)
+
+
+def peek_device_id() -> str | None:
+    """Like get_device_id(), but does not generate a new ID when there is none."""
+    dev_id = common.get(_NAMESPACE, DEVICE_ID, public=True)
+    if not dev_id:
+        return None
     return dev_id.decode()
 
 
+def _check_device_id(device_id: str) -> None:
+    _check_exact_length("DEVICE_ID", device_id)
+    for c in device_id:
+        if c not in "0123456789ABCDEFabcdef":
+            raise ValueError  # device id is not hex-encoded
+
+
+def set_device_id(device_id: str, allow_overwrite: bool = False) -> None:
+    if not allow_overwrite and common.get(_NAMESPACE, DEVICE_ID, public=True):
+        raise ValueError  # device id is already set
+    _check_device_id(device_id)
+    common.set(_NAMESPACE, DEVICE_ID, device_id.encode(), public=True)
+
+
 def get_rotation() -> int:
+    value = get_rotation_raw()
+    if value is None or value not in (0, 90, 180, 270):
+        # unset, corrupted or unsupported value
+        return 0
+    return value
+
+
+def get_rotation_raw() -> int | None:
     rotation = common.get(_NAMESPACE, _ROTATION, public=True)
     if not rotation:
-        return 0
+        return None
     return int.from_bytes(rotation, "big")
 
 
-def set_rotation(value: int) -> None:
+def _check_rotation(value: int) -> None:
+    _check_int("ROTATION", value)
     if value not in (0, 90, 180, 270):
         raise ValueError  # unsupported display rotation
-    common.set(_NAMESPACE, _ROTATION, value.to_bytes(2, "big"), True)  # public
+    if is_rotation_locked():
+        raise RuntimeError("Rotation locked")
+
+
+def set_rotation(value: int) -> None:
+    _check_rotation(value)
+    changed = value != get_rotation()
+    data = value.to_bytes(2, "big")
+    common.set_if_changed(_NAMESPACE, _ROTATION, data, True)  # public
+    if changed and _rotation_observer is not None:
+        _rotation_observer(value)
+
+
+def is_rotation_locked() -> bool:
+    return common.get_bool(_NAMESPACE, _ROTATION_LOCKED)
+
+
+def set_rotation_locked(locked: bool) -> None:
+    """While locked, set_rotation() raises RuntimeError."""
+    common.set_true_or_delete(_NAMESPACE, _ROTATION_LOCKED, locked)
+
+
+def set_rotation_observer(observer: Callable[[int], None] | None) -> None:
+    """
+    Register a callback invoked with the new rotation whenever `set_rotation`
+    changes it, so that the display can be re-oriented.
+    """
+    global _rotation_observer
+    _rotation_observer = observer
 
 
 def get_label() -> str | None:
@@ -110,20 +364,95 @@ def get_label() -> str | None:
     return label.decode()
 
 
-def set_label(label: str) -> None:
+def get_label_hash() -> bytes | None:
+    """
+    The first 8 bytes of the SHA-256 of the label, None if there is no label.
+    Tells whether the label changed without revealing it, e.g. in logs.
+    """
+    from trezor.crypto.hashlib import sha256
+
+    label = common.get(_NAMESPACE, _LABEL, True)  # public
+    if label is None:
+        return None
+    return sha256(label).digest()[:8]
+
+
+def normalize_label(label: str) -> str:
+    """
+    Returns the label without surrounding whitespace, as set_label() stores it.
+    Raises ValueError if it is too long or contains control characters, which do
+    not render. Workflows call this before any confirmation dialog.
+    """
+    label = label.strip()
     if len(label) > LABEL_MAXLENGTH:
-        raise ValueError  # label too long
-    common.set(_NAMESPACE, _LABEL, label.encode(), True)  # public
+        raise ValueError("Label too long")
+    for c in label:
+        if ord(c) < 0x20 or ord(c) == 0x7F:
+            raise ValueError("Label contains control characters")
+    return label
+
+
+def set_label(label: str) -> None:
+    label = normalize_label(label)
+    if not label:
+        # empty label reverts to the default one
+        common.delete(_NAMESPACE, _LABEL, public=True)
+        return
+    common.set_if_changed(_NAMESPACE, _LABEL, label.encode(), True)  # public
+
+
+def get_label_color() -> int | None:
+    """The color of the label as 0xRRGGBB, None for the theme default."""
+    color = common.get(_NAMESPACE, _LABEL_COLOR, public=True)
+    if color is None:
+        return None
+    return int.from_bytes(color, "big")
+
+
+def set_label_color(color: int) -> None:
+    _check_int("LABEL_COLOR", color)
+    if not 0 <= color <= 0xFF_FFFF:
+        raise ValueError  # color does not fit in 24 bits
+    data = color.to_bytes(4, "big")
+    common.set_if_changed(_NAMESPACE, _LABEL_COLOR, data, public=True)
+
+
+def get_brightness() -> int:
+    brightness = get_brightness_raw()
+    if brightness is None:
+        return BRIGHTNESS_DEFAULT
+    return brightness
+
+
+def get_brightness_raw() -> int | None:
+    brightness = common.get(_NAMESPACE, _BRIGHTNESS, public=True)
+    if not brightness:
+        return None
+    return brightness[0]
+
+
+def set_brightness(brightness: int) -> None:
+    _check_int("BRIGHTNESS", brightness)
+    if not 0 <= brightness <= 255:
+        raise ValueError  # invalid brightness
+    data = brightness.to_bytes(1, "big")
+    common.set_if_changed(_NAMESPACE, _BRIGHTNESS, data, public=True)
 
 
 def get_mnemonic_secret() -> bytes | None:
     return common.get(_NAMESPACE, _MNEMONIC_SECRET)
 
 
-def get_backup_type() -> BackupType:
+def get_backup_type() -> BackupType | None:
+    """
+    Returns None when no mnemonic secret is stored, so that an uninitialized
+    device is not mistaken for one with a BIP-39 backup.
+    """
     from trezor.enums import BackupType
 
-    backup_type = common.get_uint8(_NAMESPACE, _BACKUP_TYPE)
+    if not common.get_length(_NAMESPACE, _MNEMONIC_SECRET):
+        return None
+    backup_type = get_backup_type_raw()
     if backup_type is None:
         backup_type = BackupType.Bip39
 
@@ -137,6 +466,27 @@ def get_backup_type() -> BackupType:
     return backup_type  # type: ignore [int-into-enum]
 
 
+def get_backup_type_raw() -> int | None:
+    return common.get_uint8(_NAMESPACE, _BACKUP_TYPE)
+
+
+def get_backup_type_name() -> str | None:
+    """
+    "Bip39", "Slip39Basic" or "Slip39Advanced" for display, None when no
+    mnemonic secret is stored.
+    """
+    from trezor.enums import BackupType
+
+    backup_type = get_backup_type()
+    if backup_type is None:
+        return None
+    return {
+        BackupType.Bip39: "Bip39",
+        BackupType.Slip39_Basic: "Slip39Basic",
+        BackupType.Slip39_Advanced: "Slip39Advanced",
+    }[backup_type]
+
+
 def is_passphrase_enabled() -> bool:
     return common.get_bool(_NAMESPACE, _USE_PASSPHRASE)
 
@@ -151,10 +501,89 @@ def get_homescreen() -> bytes | None:
     return common.get(_NAMESPACE, _HOMESCREEN, public=True)
 
 
-def set_homescreen(homescreen: bytes) -> None:
-    if len(homescreen) > HOMESCREEN_MAXSIZE:
+def get_homescreen_size() -> int:
+    return common.get_length(_NAMESPACE, _HOMESCREEN, public=True) or 0
+
+
+def has_homescreen() -> bool:
+    return get_homescreen_size() > 0
+
+
+def get_homescreen_into(buffer: bytearray) -> int:
+    """
+    Reads the homescreen into `buffer` without allocating a copy. Returns the
+    number of bytes written, 0 if there is no homescreen. Raises ValueError if
+    the buffer is too small.
+    """
+    return common.get_into(_NAMESPACE, _HOMESCREEN, buffer, public=True) or 0
+
+
+def get_homescreen_checksum() -> int:
+    """CRC32 of the stored homescreen, 0 if there is none."""
+    from trezor.crypto import crc
+
+    length = get_homescreen_size()
+    if not length:
+        return 0
+    buffer = bytearray(length)
+    get_homescreen_into(buffer)
+    return crc.crc32(buffer)
+
+
+def get_homescreen_maxsize() -> int:
+    return _homescreen_maxsize
+
+
+def set_homescreen_maxsize(size: int) -> None:
+    """
+    Lowers the homescreen size limit for models with less display memory. The
+    limit can never exceed HOMESCREEN_MAXSIZE.
+    """
+    global _homescreen_maxsize
+    if not 0 < size <= HOMESCREEN_MAXSIZE:
+        raise ValueError  # invalid homescreen size limit
+    _homescreen_maxsize = size
+
+
+def set_homescreen(homescreen: bytes, *, format: int | None = None) -> None:
+    """
+    An empty `homescreen` reverts to the default one. If `format` is given, a
+    non-empty `homescreen` must start with the magic bytes of that format.
+    """
+    if len(homescreen) > _homescreen_maxsize:
         raise ValueError  # homescreen too large
-    common.set(_NAMESPACE, _HOMESCREEN, homescreen, public=True)
+    if format is not None and homescreen:
+        if format not in _HOMESCREEN_MAGIC:
+            raise ValueError  # unknown homescreen format
+        for magic in _HOMESCREEN_MAGIC[format]:
+            if homescreen[: len(magic)] == magic:
+                break
+        else:
+            raise ValueError  # homescreen does not match the format
+    if not homescreen:
+        common.delete(_NAMESPACE, _HOMESCREEN, public=True)
+        return
+    common.set_if_changed(_NAMESPACE, _HOMESCREEN, homescreen, public=True)
+
+
+def check_mnemonic_secret(secret: bytes, backup_type: BackupType) -> None:
+    """
+    Raises ValueError if the length of the secret does not fit the backup type,
+    as store_mnemonic_secret() does. Workflows call this before writing anything.
+    """
+    from trezor.enums import BackupType
+
+    if backup_type == BackupType.Bip39:
+        if not _BIP39_SECRET_MIN_LENGTH <= len(secret) <= _BIP39_SECRET_MAX_LENGTH:
+            raise ValueError("Invalid BIP-39 mnemonic length")
+    elif backup_type in (BackupType.Slip39_Basic, BackupType.Slip39_Advanced):
+        if (
+            not _SLIP39_SECRET_MIN_LENGTH <= len(secret) <= _SLIP39_SECRET_MAX_LENGTH
+            or len(secret) % 2
+        ):
+            raise ValueError("Invalid SLIP-39 master secret length")
+    else:
+        raise ValueError("Unknown backup type")
 
 
 def store_mnemonic_secret(
@@ -163,13 +592,32 @@ def store_mnemonic_secret(
     needs_backup: bool = False,
     no_backup: bool = False,
 ) -> None:
+    """
+    The secret is the mnemonic for BIP-39 and the encrypted master secret for
+    SLIP-39. Raises ValueError if its length does not fit the backup type. The
+    device is marked as initialized by finalize_initialization() afterwards.
+    """
+    if needs_backup and no_backup:
+        raise ValueError("needs_backup and no_backup are mutually exclusive")
+    check_mnemonic_secret(secret, backup_type)
     set_version(common.STORAGE_VERSION_CURRENT)
     common.set(_NAMESPACE, _MNEMONIC_SECRET, secret)
     common.set_uint8(_NAMESPACE, _BACKUP_TYPE, backup_type)
     common.set_true_or_delete(_NAMESPACE, _NO_BACKUP, no_backup)
+    common.set_true_or_delete(_NAMESPACE, _NEEDS_BACKUP, needs_backup)
+
+
+def finalize_initialization() -> None:
+    """
+    Marks the device as initialized. Raises RuntimeError unless both the storage
+    version and the mnemonic secret are stored, so that an initialized device is
+    never left without them.
+    """
+    if not is_version_stored():
+        raise RuntimeError  # storage version is missing
+    if not common.get(_NAMESPACE, _MNEMONIC_SECRET):
+        raise RuntimeError  # mnemonic secret is missing
     common.set_bool(_NAMESPACE, INITIALIZED, True, public=True)
-    if not no_backup:
-        common.set_true_or_delete(_NAMESPACE, _NEEDS_BACKUP, needs_backup)
 
 
 def needs_backup() -> bool:
@@ -188,17 +636,36 @@ def set_unfinished_backup(state: bool) -> None:
     common.set_bool(_NAMESPACE, _UNFINISHED_BACKUP, state)
 
 
+def has_wipe_code() -> bool:
+    from trezor import config
+
+    return config.has_wipe_code()
+
+
 def no_backup() -> bool:
     return common.get_bool(_NAMESPACE, _NO_BACKUP)
 
 
+def get_backup_state() -> dict[str, Any]:
+    return {
+        "needs_backup": needs_backup(),
+        "no_backup": no_backup(),
+        "unfinished_backup": unfinished_backup(),
+        "backup_type": get_backup_type(),
+    }
+
+
 def get_passphrase_always_on_device() -> bool:
     """
     This is backwards compatible with _PASSPHRASE_SOURCE:
     - If ASK(0) => returns False, the check against b"\x01" in get_bool fails.
     - If DEVICE(1) => returns True, the check against b"\x01" in get_bool succeeds.
     - If HOST(2) => returns False, the check against b"\x01" in get_bool fails.
+    Always returns False while passphrase is disabled, in case a stale value was
+    left behind.
     """
+    if not is_passphrase_enabled():
+        return False
     return common.get_bool(_NAMESPACE, _PASSPHRASE_ALWAYS_ON_DEVICE)
 
 
@@ -206,15 +673,46 @@ def set_passphrase_always_on_device(enable: bool) -> None:
     common.set_bool(_NAMESPACE, _PASSPHRASE_ALWAYS_ON_DEVICE, enable)
 
 
+def get_passphrase_source() -> int:
+    """
+    Returns the field as the legacy _PASSPHRASE_SOURCE value, i.e. ASK(0),
+    DEVICE(1) or HOST(2). Unset or unknown values are reported as ASK(0).
+    """
+    b = common.get(_NAMESPACE, _PASSPHRASE_ALWAYS_ON_DEVICE)
+    if b is None or len(b) != 1 or b[0] > 2:
+        return 0
+    return b[0]
+
+
 def get_flags() -> int:
+    return get_flags_raw() or 0
+
+
+def get_flags_raw() -> int | None:
     b = common.get(_NAMESPACE, _FLAGS)
     if b is None:
-        return 0
-    else:
-        return int.from_bytes(b, "big")
+        return None
+    return int.from_bytes(b, "big")
+
+
+def get_flags_decoded() -> dict[str, bool]:
+    """Maps the name of every bit in FLAGS_KNOWN_MASK to whether it is set."""
+    flags = get_flags()
+    return {
+        f"flag_{bit}": bool(flags & (1 << bit))
+        for bit in range(32)
+        if FLAGS_KNOWN_MASK & (1 << bit)
+    }
 
 
-def set_flags(flags: int) -> None:
+def set_flags(flags: int, strict: bool = False) -> int:
+    """
+    Sets the given bits, flags can never be unset. With `strict`, setting any bit
+    outside of FLAGS_KNOWN_MASK raises ValueError. Returns the resulting flags.
+    """
+    _check_int("FLAGS", flags)
+    if strict and flags & ~FLAGS_KNOWN_MASK:
+        raise ValueError  # reserved flag bits
     b = common.get(_NAMESPACE, _FLAGS)
     if b is None:
         i = 0
@@ -223,6 +721,26 @@ def set_flags(flags: int) -> None:
     flags = (flags | i) & 0xFFFF_FFFF
     if flags != i:
         common.set(_NAMESPACE, _FLAGS, flags.to_bytes(4, "big"))
+    return flags
+
+
+def reset_flags() -> None:
+    """Deletes the flags field, so that get_flags() reads 0 again."""
+    common.delete(_NAMESPACE, _FLAGS)
+
+
+def snapshot_flags_at_boot() -> None:
+    storage.cache.set(
+        storage.cache.STORAGE_DEVICE_FLAGS_AT_BOOT, get_flags().to_bytes(4, "big")
+    )
+
+
+def get_flags_changed_since_boot() -> int:
+    """
+    Flag bits that differ from the snapshot taken in `storage.init_unlocked()`.
+    """
+    boot_flags = storage.cache.get(storage.cache.STORAGE_DEVICE_FLAGS_AT_BOOT, b"")
+    return get_flags() ^ int.from_bytes(boot_flags, "big")
 
 
 def _normalize_autolock_delay(delay_ms: int) -> int:
@@ -232,23 +750,91 @@ def _normalize_autolock_delay(delay_ms: int) -> int:
 
 
 def get_autolock_delay_ms() -> int:
-    b = common.get(_NAMESPACE, _AUTOLOCK_DELAY_MS)
-    if b is None:
+    """
+    Same as get_autolock_delay_ms_effective(), which should be preferred in new
+    code to make the distinction from get_autolock_delay_ms_raw() explicit.
+    """
+    return get_autolock_delay_ms_effective()
+
+
+def get_autolock_delay_ms_effective() -> int:
+    """The delay actually in use, i.e. the stored one clamped to the limits."""
+    raw = get_autolock_delay_ms_raw()
+    if raw is None:
         return AUTOLOCK_DELAY_DEFAULT
     else:
-        return _normalize_autolock_delay(int.from_bytes(b, "big"))
+        return _normalize_autolock_delay(raw)
 
 
-def set_autolock_delay_ms(delay_ms: int) -> None:
+def get_autolock_delay_ms_clamped(min_ms: int, max_ms: int) -> int:
+    """
+    The effective delay clamped to the given range, intersected with
+    AUTOLOCK_DELAY_MINIMUM and AUTOLOCK_DELAY_MAXIMUM. Raises ValueError if the
+    resulting range is empty.
+    """
+    min_ms = max(min_ms, AUTOLOCK_DELAY_MINIMUM)
+    max_ms = min(max_ms, AUTOLOCK_DELAY_MAXIMUM)
+    if min_ms > max_ms:
+        raise ValueError  # empty range
+    return min(max(get_autolock_delay_ms_effective(), min_ms), max_ms)
+
+
+def get_autolock_delay_ms_raw() -> int | None:
+    """The stored delay as is, None if it was never set."""
+    b = common.get(_NAMESPACE, _AUTOLOCK_DELAY_MS)
+    if b is None:
+        return None
+    return int.from_bytes(b, "big")
+
+
+def _check_autolock_delay(delay_ms: int) -> None:
+    _check_int("AUTOLOCK_DELAY_MS", delay_ms)
+    if delay_ms > AUTOLOCK_DELAY_MAXIMUM:
+        raise ValueError  # autolock delay too long
+
+
+def set_autolock_delay_ms(delay_ms: int, clamp: bool = False) -> None:
+    """
+    Values below AUTOLOCK_DELAY_MINIMUM are raised to it. Values above
+    AUTOLOCK_DELAY_MAXIMUM raise ValueError, unless `clamp` is set.
+    """
+    if clamp:
+        _check_int("AUTOLOCK_DELAY_MS", delay_ms)
+    else:
+        _check_autolock_delay(delay_ms)
     delay_ms = _normalize_autolock_delay(delay_ms)
-    common.set(_NAMESPACE, _AUTOLOCK_DELAY_MS, delay_ms.to_bytes(4, "big"))
+    changed = delay_ms != get_autolock_delay_ms()
+    common.set_if_changed(_NAMESPACE, _AUTOLOCK_DELAY_MS, delay_ms.to_bytes(4, "big"))
+    if changed and _autolock_delay_observer is not None:
+        _autolock_delay_observer(delay_ms)
+
+
+def set_autolock_delay_observer(observer: Callable[[int], None] | None) -> None:
+    """
+    Register a callback invoked with the new effective delay whenever
+    `set_autolock_delay_ms` changes it, so that the idle timer can be re-armed.
+    """
+    global _autolock_delay_observer
+    _autolock_delay_observer = observer
 
 
 def next_u2f_counter() -> int:
     return common.next_counter(_NAMESPACE, U2F_COUNTER, writable_locked=True)
 
 
-def set_u2f_counter(count: int) -> None:
+def get_u2f_counter() -> int | None:
+    return common.get_counter(_NAMESPACE, U2F_COUNTER, writable_locked=True)
+
+
+def set_u2f_counter(count: int, force: bool = False) -> None:
+    """
+    Refuses to decrease the counter, which would weaken the U2F replay protection,
+    unless `force` is set.
+    """
+    if not force:
+        current = get_u2f_counter()
+        if current is not None and count < current:
+            raise ValueError  # counter must not decrease
     common.set_counter(_NAMESPACE, U2F_COUNTER, count, writable_locked=True)
 
 
@@ -258,11 +844,14 @@ def set_slip39_identifier(identifier: int) -> None:
     Not to be confused with recovery.identifier, which is stored only during
     the recovery process and it is copied here upon success.
     """
+    _check_int("SLIP39_IDENTIFIER", identifier)
     common.set_uint16(_NAMESPACE, _SLIP39_IDENTIFIER, identifier)
 
 
-def get_slip39_identifier() -> int | None:
+def get_slip39_identifier(require_init: bool = False) -> int | None:
     """The device's actual SLIP-39 identifier used in passphrase derivation."""
+    if require_init:
+        require_initialized()
     return common.get_uint16(_NAMESPACE, _SLIP39_IDENTIFIER)
 
 
@@ -272,16 +861,59 @@ def set_slip39_iteration_exponent(exponent: int) -> None:
     Not to be confused with recovery.iteration_exponent, which is stored only during
     the recovery process and it is copied here upon success.
     """
+    _check_int("SLIP39_ITERATION_EXPONENT", exponent)
+    if not 0 <= exponent <= _SLIP39_ITERATION_EXPONENT_MAX:
+        raise ValueError  # invalid iteration exponent
     common.set_uint8(_NAMESPACE, _SLIP39_ITERATION_EXPONENT, exponent)
 
 
-def get_slip39_iteration_exponent() -> int | None:
+def get_slip39_iteration_exponent(require_init: bool = False) -> int | None:
     """
     The device's actual SLIP-39 iteration exponent used in passphrase derivation.
     """
+    if require_init:
+        require_initialized()
     return common.get_uint8(_NAMESPACE, _SLIP39_ITERATION_EXPONENT)
 
 
+def set_slip39_group_threshold(threshold: int) -> None:
+    from trezor.crypto.slip39 import MAX_GROUP_COUNT
+
+    _check_int("SLIP39_GROUP_THRESHOLD", threshold)
+    if not 1 <= threshold <= MAX_GROUP_COUNT:
+        raise ValueError  # invalid group threshold
+    common.set_uint8(_NAMESPACE, _SLIP39_GROUP_THRESHOLD, threshold)
+
+
+def get_slip39_group_threshold(require_init: bool = False) -> int | None:
+    if require_init:
+        require_initialized()
+    return common.get_uint8(_NAMESPACE, _SLIP39_GROUP_THRESHOLD)
+
+
+def set_slip39_group_count(group_count: int) -> None:
+    from trezor.crypto.slip39 import MAX_GROUP_COUNT
+
+    _check_int("SLIP39_GROUP_COUNT", group_count)
+    if not 1 <= group_count <= MAX_GROUP_COUNT:
+        raise ValueError  # invalid group count
+    common.set_uint8(_NAMESPACE, _SLIP39_GROUP_COUNT, group_count)
+
+
+def get_slip39_group_count(require_init: bool = False) -> int | None:
+    if require_init:
+        require_initialized()
+    return common.get_uint8(_NAMESPACE, _SLIP39_GROUP_COUNT)
+
+
+def clear_slip39_state() -> None:
+    """Deletes all SLIP-39 parameters. The mnemonic secret is left intact."""
+    common.delete(_NAMESPACE, _SLIP39_IDENTIFIER)
+    common.delete(_NAMESPACE, _SLIP39_ITERATION_EXPONENT)
+    common.delete(_NAMESPACE, _SLIP39_GROUP_THRESHOLD)
+    common.delete(_NAMESPACE, _SLIP39_GROUP_COUNT)
+
+
 def get_sd_salt_auth_key() -> bytes | None:
     """
     The key used to check the authenticity of the SD card salt.
@@ -292,32 +924,74 @@ def get_sd_salt_auth_key() -> bytes | None:
     return auth_key
 
 
+def has_sd_salt_auth_key() -> bool:
+    """Whether SD card protection is enabled, without reading the key itself."""
+    return bool(common.get_length(_NAMESPACE, _SD_SALT_AUTH_KEY, public=True))
+
+
 def set_sd_salt_auth_key(auth_key: bytes | None) -> None:
     """
     The key used to check the authenticity of the SD card salt.
+    None or an empty key disables SD card protection.
     """
-    if auth_key is not None:
-        if len(auth_key) != SD_SALT_AUTH_KEY_LEN_BYTES:
-            raise ValueError
-        return common.set(_NAMESPACE, _SD_SALT_AUTH_KEY, auth_key, public=True)
+    if auth_key:
+        _check_exact_length("SD_SALT_AUTH_KEY", auth_key)
+        return common.set_if_changed(
+            _NAMESPACE, _SD_SALT_AUTH_KEY, auth_key, public=True
+        )
     else:
         return common.delete(_NAMESPACE, _SD_SALT_AUTH_KEY, public=True)
 
 
 # do not use this function directly, see apps.common.safety_checks instead
 def safety_check_level() -> StorageSafetyCheckLevel:
-    level = common.get_uint8(_NAMESPACE, _SAFETY_CHECK_LEVEL)
+    level = safety_check_level_raw()
     if level not in (SAFETY_CHECK_LEVEL_STRICT, SAFETY_CHECK_LEVEL_PROMPT):
         return _DEFAULT_SAFETY_CHECK_LEVEL
     else:
         return level  # type: ignore [int-into-enum]
 
 
+def safety_check_level_raw() -> int | None:
+    return common.get_uint8(_NAMESPACE, _SAFETY_CHECK_LEVEL)
+
+
 # do not use this function directly, see apps.common.safety_checks instead
-def set_safety_check_level(level: StorageSafetyCheckLevel) -> None:
+def set_safety_check_level(
+    level: StorageSafetyCheckLevel, source: int | None = None
+) -> None:
+    """
+    Stores the safety check level along with who changed it. Without a source,
+    the previously recorded source is kept.
+    """
     if level not in (SAFETY_CHECK_LEVEL_STRICT, SAFETY_CHECK_LEVEL_PROMPT):
         raise ValueError
+    if source not in (None, SAFETY_CHECK_SOURCE_HOST):
+        raise ValueError  # unknown source
     common.set_uint8(_NAMESPACE, _SAFETY_CHECK_LEVEL, level)
+    if source is not None:
+        common.set_uint8(_NAMESPACE, _SAFETY_CHECK_LEVEL_SOURCE, source)
+
+
+def get_safety_check_level_source() -> int:
+    """
+    Returns who last changed the safety check level, or SAFETY_CHECK_SOURCE_UNSET
+    if it was never recorded.
+    """
+    source = common.get_uint8(_NAMESPACE, _SAFETY_CHECK_LEVEL_SOURCE)
+    if source is None:
+        return SAFETY_CHECK_SOURCE_UNSET
+    return source
+
+
+# do not use this function directly, see apps.common.safety_checks instead
+def is_safety_check_strict() -> bool:
+    return safety_check_level() == SAFETY_CHECK_LEVEL_STRICT
+
+
+# do not use this function directly, see apps.common.safety_checks instead
+def is_safety_check_prompt() -> bool:
+    return safety_check_level() == SAFETY_CHECK_LEVEL_PROMPT
 
 
 @storage.cache.stored(storage.cache.STORAGE_DEVICE_EXPERIMENTAL_FEATURES)
@@ -336,3 +1010,115 @@ def set_experimental_features(enabled: bool) -> None:
     cached_bytes = b"\x01" if enabled else b""
     storage.cache.set(storage.cache.STORAGE_DEVICE_EXPERIMENTAL_FEATURES, cached_bytes)
     common.set_true_or_delete(_NAMESPACE, _EXPERIMENTAL_FEATURES, enabled)
+    if not enabled:
+        for key, public in _EXPERIMENTAL_ONLY_FIELDS:
+            common.delete(_NAMESPACE, key, public)
+
+
+def validate_device_state() -> list[str]:
+    """
+    Checks invariants between the stored fields. Returns a description of every
+    inconsistency found, an empty list means the state is consistent.
+    """
+    from trezor.enums import BackupType
+
+    problems = []
+    if is_initialized() and not is_version_stored():
+        problems.append("initialized without a storage version")
+    always_on_device = common.get_bool(_NAMESPACE, _PASSPHRASE_ALWAYS_ON_DEVICE)
+    if always_on_device and not is_passphrase_enabled():
+        problems.append("passphrase always on device while passphrase is disabled")
+    backup_type = get_backup_type_raw()
+    if backup_type is not None and backup_type not in (
+        BackupType.Bip39,
+        BackupType.Slip39_Basic,
+        BackupType.Slip39_Advanced,
+    ):
+        problems.append(f"unknown backup type {backup_type}")
+    if needs_backup() and no_backup():
+        problems.append("needs backup while backup is disabled")
+    return problems
+
+
+def snapshot() -> dict[str, Any]:
+    """
+    Reads the commonly used fields at once: initialized, device_id (without
+    generating one), label, rotation, brightness, passphrase_enabled,
+    passphrase_always_on_device, autolock_delay_ms, safety_check_level,
+    experimental_features, flags and the get_backup_state() fields.
+
+    The function is synchronous, so no write can happen between the reads and the
+    returned values are consistent with each other.
+    """
+    state = get_backup_state()
+    state["initialized"] = is_initialized()
+    state["device_id"] = peek_device_id()
+    state["label"] = get_label()
+    state["rotation"] = get_rotation()
+    state["brightness"] = get_brightness()
+    state["passphrase_enabled"] = is_passphrase_enabled()
+    state["passphrase_always_on_device"] = get_passphrase_always_on_device()
+    state["autolock_delay_ms"] = get_autolock_delay_ms()
+    state["safety_check_level"] = safety_check_level()
+    state["experimental_features"] = get_experimental_features()
+    state["flags"] = get_flags()
+    return state
+
+
+def describe_field(name: str) -> dict[str, Any]:
+    """
+    Returns the app id, key, visibility, maximum size and value type of the named
+    field. Raises KeyError for an unknown field name. The homescreen reports the
+    limit currently in effect, see set_homescreen_maxsize().
+    """
+    key, public, max_size, value_type = _FIELDS[name]
+    if key == _HOMESCREEN:
+        max_size = _homescreen_maxsize
+    return {
+        "name": name,
+        "app": _NAMESPACE,
+        "key": key,
+        "type": value_type,
+        "public": public,
+        "max_size": max_size,
+    }
+
+
+def list_fields() -> list[dict[str, Any]]:
+    """Returns describe_field() of every field, ordered by key."""
+    names = sorted(_FIELDS, key=lambda name: _FIELDS[name][0])
+    return [describe_field(name) for name in names]
+
+
+if __debug__:
+
+    def debug_dump_fields() -> dict[str, bytes | None]:
+        """
+        Returns the raw stored value of every field, None for absent ones.
+        Only available in debug builds.
+        """
+        return {
+            name: common.get(_NAMESPACE, key, public)
+            for name, (key, public, _, _) in _FIELDS.items()
+        }
+
+
+def set_many(values: dict[str, Any]) -> None:
+    """
+    Sets several fields at once. All the values are validated before anything is
+    written, so a single invalid value leaves the storage untouched.
+    Supported keys are "label", "rotation" and "autolock_delay_ms".
+    """
+    setters = {
+        "label": (normalize_label, set_label),
+        "rotation": (_check_rotation, set_rotation),
+        "autolock_delay_ms": (_check_autolock_delay, set_autolock_delay_ms),
+    }
+    for name, value in values.items():
+        if name not in setters:
+            raise ValueError  # unsupported field
+        check, _ = setters[name]
+        check(value)
+    for name, value in values.items():
+        _, setter = setters[name]
+        setter(value)
@@ -122,10 +122,39 @@ def fetch_slip39_remaining_shares() -> list[int] | None:
     return list(remaining[:group_count])
 
 
-def end_progress() -> None:
+def verify_before_commit() -> None:
+    """
+    Checks that the recovery SLIP-39 identifier and iteration exponent match
+    every stored share. Raises ValueError otherwise, so that the shares are not
+    cleared by `end_progress` without a valid result.
+    """
+    from trezor.crypto.slip39 import decode_mnemonic
     from . import recovery_shares
 
     _require_progress()
+    identifier = get_slip39_identifier()
+    exponent = get_slip39_iteration_exponent()
+    if identifier is None or exponent is None:
+        raise RuntimeError
+    for group_index in range(get_slip39_group_count()):
+        for mnemonic in recovery_shares.fetch_group(group_index):
+            share = decode_mnemonic(mnemonic)
+            if share.identifier != identifier or share.iteration_exponent != exponent:
+                raise ValueError("Shares do not match the recovery metadata")
+
+
+def end_progress() -> None:
+    _require_progress()
+    abort_recovery()
+
+
+def abort_recovery() -> None:
+    """
+    Deletes all the recovery state, including the stored shares, whether or not a
+    recovery is in progress. The device secret is never touched.
+    """
+    from . import recovery_shares
+
     common.delete(_NAMESPACE, _IN_PROGRESS)
     common.delete(_NAMESPACE, _DRY_RUN)
     common.delete(_NAMESPACE, _SLIP39_IDENTIFIER)
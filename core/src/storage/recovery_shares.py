@@ -1,37 +1,78 @@
-from storage import common
+from micropython import const
+from typing import TYPE_CHECKING
+
+from storage import common, device
 from trezor.crypto import slip39
 
+if TYPE_CHECKING:
+    from typing import Iterator
+
 # Mnemonics stored during SLIP-39 recovery process.
-# Each mnemonic is stored under key = index.
+# Each mnemonic is stored under key = index + group_index * MAX_SHARE_COUNT.
+
+# Longest mnemonic accepted, in bytes.
+_MAX_SHARE_LENGTH = const(256)
+
 
+def _key(index: int, group_index: int) -> int:
+    if not 0 <= index < slip39.MAX_SHARE_COUNT:
+        raise ValueError  # invalid share index
+    if not 0 <= group_index < slip39.MAX_GROUP_COUNT:
+        raise ValueError  # invalid group index
+    return index + group_index * slip39.MAX_SHARE_COUNT
 
-def set(index: int, group_index: int, mnemonic: str) -> None:
-    common.set(
-        common.APP_RECOVERY_SHARES,
-        index + group_index * slip39.MAX_SHARE_COUNT,
-        mnemonic.encode(),
-    )
+
+def set(index: int, group_index: int, mnemonic: str, force: bool = False) -> None:
+    """
+    Raises RuntimeError on an initialized device, where recovery shares are
+    unexpected, unless `force` is set, as for a dry run.
+    """
+    if not force and device.is_initialized():
+        raise RuntimeError  # device is already initialized
+    data = mnemonic.encode()
+    if len(data) > _MAX_SHARE_LENGTH:
+        raise ValueError("Share too long")
+    common.set(common.APP_RECOVERY_SHARES, _key(index, group_index), data)
 
 
 def get(index: int, group_index: int) -> str | None:
-    m = common.get(
-        common.APP_RECOVERY_SHARES, index + group_index * slip39.MAX_SHARE_COUNT
-    )
+    m = common.get(common.APP_RECOVERY_SHARES, _key(index, group_index))
     if m:
         return m.decode()
     return None
 
 
-def fetch_group(group_index: int) -> list[str]:
-    mnemonics = []
+def has(index: int, group_index: int) -> bool:
+    """Whether a mnemonic is stored in the slot, without reading it."""
+    return bool(common.get_length(common.APP_RECOVERY_SHARES, _key(index, group_index)))
+
+
+def iter_group(group_index: int) -> Iterator[str]:
+    """Yields the stored mnemonics of a group one by one, reading them lazily."""
     for index in range(slip39.MAX_SHARE_COUNT):
         m = get(index, group_index)
         if m:
-            mnemonics.append(m)
+            yield m
 
-    return mnemonics
+
+def fetch_group(group_index: int) -> list[str]:
+    return list(iter_group(group_index))
+
+
+def total_share_count() -> int:
+    """Number of stored mnemonics across all groups, without reading them."""
+    count = 0
+    for index in range(slip39.MAX_SHARE_COUNT * slip39.MAX_GROUP_COUNT):
+        if common.get_length(common.APP_RECOVERY_SHARES, index):
+            count += 1
+    return count
 
 
 def delete() -> None:
     for index in range(slip39.MAX_SHARE_COUNT * slip39.MAX_GROUP_COUNT):
         common.delete(common.APP_RECOVERY_SHARES, index)
+
+
+def delete_group(group_index: int) -> None:
+    for index in range(slip39.MAX_SHARE_COUNT):
+        common.delete(common.APP_RECOVERY_SHARES, _key(index, group_index))
@@ -21,7 +21,7 @@ class WrongSdCard(Exception):
 
 
 def is_enabled() -> bool:
-    return storage.device.get_sd_salt_auth_key() is not None
+    return storage.device.has_sd_salt_auth_key()
 
 
 def compute_auth_tag(salt: bytes, auth_key: bytes) -> bytes:
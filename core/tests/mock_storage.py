@@ -3,23 +3,47 @@ from mock import patch
 import storage.common
 
 class MockStorage:
-    PATCH_METHODS = ("get", "set", "delete")
+    PATCH_METHODS = ("get", "get_length", "get_into", "set", "delete")
 
     def __init__(self):
         self.namespace = {}
+        self.writes = 0
+        self.reads = 0
         self.patches = [
             patch(storage.common, method, getattr(self, method))
             for method in self.PATCH_METHODS
         ]
 
     def set(self, app: int, key: int, data: bytes, public: bool = False) -> None:
+        self.writes += 1
         self.namespace.setdefault(app, {})
         self.namespace[app][key] = data
 
-    def get(self, app: int, key: int, public: bool = False) -> bytes | None:
+    def _lookup(self, app: int, key: int) -> bytes | None:
+        self.reads += 1
         self.namespace.setdefault(app, {})
         return self.namespace[app].get(key)
 
+    def get(self, app: int, key: int, public: bool = False) -> bytes | None:
+        return self._lookup(app, key)
+
+    def get_length(self, app: int, key: int, public: bool = False) -> int | None:
+        data = self._lookup(app, key)
+        if data is None:
+            return None
+        return len(data)
+
+    def get_into(
+        self, app: int, key: int, buffer: bytearray, public: bool = False
+    ) -> int | None:
+        data = self._lookup(app, key)
+        if data is None:
+            return None
+        if len(data) > len(buffer):
+            raise ValueError("Buffer too small.")
+        buffer[: len(data)] = data
+        return len(data)
+
     def delete(self, app: int, key: int, public: bool = False) -> None:
         self.namespace.setdefault(app, {})
         self.namespace[app].pop(key, None)
@@ -58,6 +58,24 @@ class TestSlip39(unittest.TestCase):
         self.assertEqual(storage.recovery_shares.get(share.index, share.group_index), third)
         self.assertEqual(storage.recovery_shares.fetch_group(share.group_index), [second, third, first])  # ordered by index
 
+    @mock_storage
+    def test_process_slip39_dry_run(self):
+        storage.device.store_mnemonic_secret(bytes(16), BackupType.Slip39_Basic)
+        storage.device.finalize_initialization()
+
+        # shares can't be stored on an initialized device, except for a dry run
+        storage.recovery.set_in_progress(True)
+        with self.assertRaises(RuntimeError):
+            process_slip39(MNEMONIC_SLIP39_BASIC_20_3of6[0])
+
+        storage.recovery.end_progress()
+        storage.recovery.set_in_progress(True)
+        storage.recovery.set_dry_run(True)
+        first = MNEMONIC_SLIP39_BASIC_20_3of6[0]
+        secret, share = process_slip39(first)
+        self.assertIsNone(secret)
+        self.assertEqual(storage.recovery_shares.get(share.index, share.group_index), first)
+
     @mock_storage
     def test_process_slip39_advanced(self):
         storage.recovery.set_in_progress(True)
@@ -0,0 +1,1090 @@
+from common import *
+from mock import patch
+from mock_storage import MockStorage, mock_storage
+
+from storage import cache, common, device
+from trezor import config
+from trezor.enums import BackupType
+
+BIP39_SECRET = b"all all all all all all all all all all all all"
+SLIP39_SECRET = bytes(range(16))
+
+
+class TestStorageDevice(unittest.TestCase):
+    def setUp(self):
+        cache.clear_all()
+
+    @mock_storage
+    def test_flags_changed_since_boot(self):
+        device.set_flags(0x01)
+        device.snapshot_flags_at_boot()
+        self.assertEqual(device.get_flags_changed_since_boot(), 0)
+
+        device.set_flags(0x04)
+        self.assertEqual(device.get_flags(), 0x05)
+        self.assertEqual(device.get_flags_changed_since_boot(), 0x04)
+
+    @mock_storage
+    def test_set_device_id(self):
+        device.set_device_id("0123456789ABCDEF01234567")
+        self.assertEqual(device.get_device_id(), "0123456789ABCDEF01234567")
+
+        with self.assertRaises(ValueError):
+            device.set_device_id("0123456789ABCDEF")
+        with self.assertRaises(ValueError):
+            device.set_device_id("0123456789ABCDEF0123456Z")
+        self.assertEqual(device.get_device_id(), "0123456789ABCDEF01234567")
+
+    @mock_storage
+    def test_set_device_id_overwrite(self):
+        device.set_device_id("0123456789ABCDEF01234567")
+        with self.assertRaises(ValueError):
+            device.set_device_id("FEDCBA9876543210FEDCBA98")
+        self.assertEqual(device.get_device_id(), "0123456789ABCDEF01234567")
+
+        device.set_device_id("FEDCBA9876543210FEDCBA98", allow_overwrite=True)
+        self.assertEqual(device.get_device_id(), "FEDCBA9876543210FEDCBA98")
+
+    @mock_storage
+    def test_autolock_delay_observer(self):
+        calls = []
+        device.set_autolock_delay_observer(calls.append)
+        try:
+            device.set_autolock_delay_ms(device.AUTOLOCK_DELAY_DEFAULT)
+            self.assertEqual(calls, [])
+
+            device.set_autolock_delay_ms(device.AUTOLOCK_DELAY_DEFAULT * 2)
+            self.assertEqual(calls, [device.AUTOLOCK_DELAY_DEFAULT * 2])
+            device.set_autolock_delay_ms(device.AUTOLOCK_DELAY_DEFAULT * 2)
+            self.assertEqual(len(calls), 1)
+
+            # the observer gets the normalized value
+            device.set_autolock_delay_ms(1)
+            self.assertEqual(calls[-1], device.AUTOLOCK_DELAY_MINIMUM)
+        finally:
+            device.set_autolock_delay_observer(None)
+
+    @mock_storage
+    def test_set_many(self):
+        device.set_many(
+            {"label": "My Trezor", "rotation": 90, "autolock_delay_ms": 120_000}
+        )
+        self.assertEqual(device.get_label(), "My Trezor")
+        self.assertEqual(device.get_rotation(), 90)
+        self.assertEqual(device.get_autolock_delay_ms(), 120_000)
+
+    @mock_storage
+    def test_set_many_invalid(self):
+        with self.assertRaises(ValueError):
+            device.set_many({"label": "My Trezor", "rotation": 45})
+        self.assertIsNone(device.get_label())
+        self.assertEqual(device.get_rotation(), 0)
+
+        with self.assertRaises(ValueError):
+            device.set_many({"label": "My Trezor", "language": "english"})
+        self.assertIsNone(device.get_label())
+
+    @mock_storage
+    def test_homescreen_size(self):
+        self.assertEqual(device.get_homescreen_size(), 0)
+        self.assertFalse(device.has_homescreen())
+
+        device.set_homescreen(b"\x01" * 1234)
+        self.assertEqual(device.get_homescreen_size(), 1234)
+        self.assertTrue(device.has_homescreen())
+
+    def test_describe_field(self):
+        field = device.describe_field("DEVICE_ID")
+        self.assertEqual(field["app"], 0x01)
+        self.assertEqual(field["key"], 0x00)
+        self.assertTrue(field["public"])
+        self.assertEqual(field["max_size"], 24)
+
+        self.assertFalse(device.describe_field("MNEMONIC_SECRET")["public"])
+        with self.assertRaises(KeyError):
+            device.describe_field("NONEXISTENT")
+
+    def test_field_keys_unique(self):
+        keys = [key for key, _, _, _ in device._FIELDS.values()]
+        self.assertEqual(len(keys), len(set(keys)))
+
+    @mock_storage
+    def test_get_rotation_invalid(self):
+        device.set_rotation(180)
+        self.assertEqual(device.get_rotation(), 180)
+        # _ROTATION
+        common.set(common.APP_DEVICE, 0x0F, (45).to_bytes(2, "big"), True)
+        self.assertEqual(device.get_rotation(), 0)
+
+    @mock_storage
+    def test_store_mnemonic_secret_args(self):
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39, True)
+        self.assertEqual(device.get_mnemonic_secret(), BIP39_SECRET)
+        self.assertEqual(device.get_backup_type(), BackupType.Bip39)
+        self.assertTrue(device.needs_backup())
+
+        device.store_mnemonic_secret(
+            secret=bytes(range(32)),
+            backup_type=BackupType.Slip39_Basic,
+            no_backup=True,
+        )
+        self.assertEqual(device.get_mnemonic_secret(), bytes(range(32)))
+        self.assertEqual(device.get_backup_type(), BackupType.Slip39_Basic)
+        self.assertTrue(device.no_backup())
+
+    @mock_storage
+    def test_store_mnemonic_secret_backup_flags(self):
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39, needs_backup=True)
+        self.assertTrue(device.needs_backup())
+        self.assertFalse(device.no_backup())
+
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39, no_backup=True)
+        self.assertFalse(device.needs_backup())
+        self.assertTrue(device.no_backup())
+
+        with self.assertRaises(ValueError) as e:
+            device.store_mnemonic_secret(
+                BIP39_SECRET, BackupType.Bip39, needs_backup=True, no_backup=True
+            )
+        self.assertEqual(
+            str(e.value), "needs_backup and no_backup are mutually exclusive"
+        )
+
+    @mock_storage
+    def test_get_backup_state(self):
+        device.store_mnemonic_secret(
+            SLIP39_SECRET, BackupType.Slip39_Basic, needs_backup=True
+        )
+        device.set_unfinished_backup(True)
+        state = device.get_backup_state()
+        self.assertEqual(state["needs_backup"], device.needs_backup())
+        self.assertEqual(state["no_backup"], device.no_backup())
+        self.assertEqual(state["unfinished_backup"], device.unfinished_backup())
+        self.assertEqual(state["backup_type"], device.get_backup_type())
+        self.assertTrue(state["needs_backup"])
+        self.assertFalse(state["no_backup"])
+        self.assertTrue(state["unfinished_backup"])
+        self.assertEqual(state["backup_type"], BackupType.Slip39_Basic)
+
+    @mock_storage
+    def test_slip39_group_fields(self):
+        self.assertIsNone(device.get_slip39_group_threshold())
+        self.assertIsNone(device.get_slip39_group_count())
+
+        device.set_slip39_group_threshold(2)
+        device.set_slip39_group_count(3)
+        self.assertEqual(device.get_slip39_group_threshold(), 2)
+        self.assertEqual(device.get_slip39_group_count(), 3)
+
+        for invalid in (0, 17):
+            with self.assertRaises(ValueError):
+                device.set_slip39_group_threshold(invalid)
+            with self.assertRaises(ValueError):
+                device.set_slip39_group_count(invalid)
+        self.assertEqual(device.get_slip39_group_threshold(), 2)
+        self.assertEqual(device.get_slip39_group_count(), 3)
+
+    @mock_storage
+    def test_slip39_iteration_exponent(self):
+        device.set_slip39_iteration_exponent(31)
+        self.assertEqual(device.get_slip39_iteration_exponent(), 31)
+        with self.assertRaises(ValueError):
+            device.set_slip39_iteration_exponent(32)
+        self.assertEqual(device.get_slip39_iteration_exponent(), 31)
+
+    @mock_storage
+    def test_set_label_empty(self):
+        device.set_label("My Trezor")
+        self.assertEqual(device.get_label(), "My Trezor")
+        device.set_label("")
+        self.assertIsNone(device.get_label())
+
+    if __debug__:
+
+        @mock_storage
+        def test_debug_dump_fields(self):
+            device.set_label("My Trezor")
+            dump = device.debug_dump_fields()
+            self.assertEqual(dump["LABEL"], b"My Trezor")
+            self.assertIsNone(dump["HOMESCREEN"])
+            self.assertEqual(len(dump), len(device._FIELDS))
+
+    @mock_storage
+    def test_autolock_delay_minimum(self):
+        # shorter delays are raised to the minimum, which is 10 seconds in debug
+        # builds and 1 minute otherwise
+        device.set_autolock_delay_ms(1_000)
+        minimum = 10_000 if __debug__ else 60_000
+        self.assertEqual(device.get_autolock_delay_ms_raw(), minimum)
+        self.assertEqual(device.get_autolock_delay_ms(), minimum)
+
+        device.set_autolock_delay_ms(30_000)
+        self.assertEqual(device.get_autolock_delay_ms(), max(30_000, minimum))
+
+    @mock_storage
+    def test_check_version_compatibility(self):
+        self.assertTrue(device.check_version_compatibility())
+        device.set_version(common.STORAGE_VERSION_01)
+        self.assertTrue(device.check_version_compatibility())
+        device.set_version(common.STORAGE_VERSION_CURRENT)
+        self.assertTrue(device.check_version_compatibility())
+        device.set_version(b"\x03", force=True)
+        self.assertFalse(device.check_version_compatibility())
+
+    @mock_storage
+    def test_clear_slip39_state(self):
+        device.store_mnemonic_secret(SLIP39_SECRET, BackupType.Slip39_Advanced)
+        device.set_slip39_identifier(42)
+        device.set_slip39_iteration_exponent(1)
+        device.set_slip39_group_threshold(2)
+        device.set_slip39_group_count(3)
+
+        device.clear_slip39_state()
+        self.assertIsNone(device.get_slip39_identifier())
+        self.assertIsNone(device.get_slip39_iteration_exponent())
+        self.assertIsNone(device.get_slip39_group_threshold())
+        self.assertIsNone(device.get_slip39_group_count())
+        self.assertEqual(device.get_mnemonic_secret(), SLIP39_SECRET)
+
+    @mock_storage
+    def test_safety_check_level(self):
+        # unset level falls back to strict
+        self.assertTrue(device.is_safety_check_strict())
+        self.assertFalse(device.is_safety_check_prompt())
+
+        device.set_safety_check_level(device.SAFETY_CHECK_LEVEL_PROMPT)
+        self.assertFalse(device.is_safety_check_strict())
+        self.assertTrue(device.is_safety_check_prompt())
+
+        device.set_safety_check_level(device.SAFETY_CHECK_LEVEL_STRICT)
+        self.assertTrue(device.is_safety_check_strict())
+        self.assertFalse(device.is_safety_check_prompt())
+
+        # corrupt value falls back to strict
+        common.set_uint8(common.APP_DEVICE, 0x14, 5)  # _SAFETY_CHECK_LEVEL
+        self.assertEqual(device.safety_check_level(), device.SAFETY_CHECK_LEVEL_STRICT)
+        self.assertTrue(device.is_safety_check_strict())
+        self.assertFalse(device.is_safety_check_prompt())
+
+    @mock_storage
+    def test_get_passphrase_source(self):
+        self.assertEqual(device.get_passphrase_source(), 0)
+        device.set_passphrase_enabled(True)
+        # legacy ASK, DEVICE and HOST encodings
+        for source in (0, 1, 2):
+            common.set_uint8(common.APP_DEVICE, 0x0A, source)  # _PASSPHRASE_ALWAYS_ON_DEVICE
+            self.assertEqual(device.get_passphrase_source(), source)
+            self.assertEqual(device.get_passphrase_always_on_device(), source == 1)
+
+        device.set_passphrase_always_on_device(True)
+        self.assertEqual(device.get_passphrase_source(), 1)
+
+    @mock_storage
+    def test_string_setters_length(self):
+        label = "x" * device.LABEL_MAXLENGTH
+        device.set_label(label)
+        self.assertEqual(device.get_label(), label)
+        with self.assertRaises(ValueError):
+            device.set_label(label + "x")
+        self.assertEqual(device.get_label(), label)
+
+        with self.assertRaises(ValueError):
+            device.set_device_id("")
+        with self.assertRaises(ValueError):
+            device.set_device_id("0" * 25)
+        device.set_device_id("0" * 24)
+        self.assertEqual(device.get_device_id(), "0" * 24)
+
+    @mock_storage
+    def test_set_flags_strict(self):
+        device.set_flags(0x0001, strict=True)
+        self.assertEqual(device.get_flags(), 0x0001)
+
+        with self.assertRaises(ValueError):
+            device.set_flags(0x0001_0000, strict=True)
+        self.assertEqual(device.get_flags(), 0x0001)
+
+        device.set_flags(0x0001_0000)
+        self.assertEqual(device.get_flags(), 0x0001_0001)
+
+    @mock_storage
+    def test_homescreen_checksum(self):
+        self.assertEqual(device.get_homescreen_checksum(), 0)
+        # standard CRC32 check value
+        device.set_homescreen(b"123456789")
+        self.assertEqual(device.get_homescreen_checksum(), 0xCBF4_3926)
+
+        image = b"TOIf\x90\x00\x90\x00" + bytes(range(256)) * 8
+        device.set_homescreen(image, format=device.HOMESCREEN_FORMAT_TOIF)
+        self.assertEqual(device.get_homescreen_checksum(), 0xA005_8F01)
+        device.set_homescreen(b"")
+        self.assertEqual(device.get_homescreen_checksum(), 0)
+
+    if __debug__:
+
+        @mock_storage
+        def test_raw_access(self):
+            common.set_raw(common.APP_DEVICE, 0x04, b"raw label", True)  # _LABEL
+            self.assertEqual(device.get_label(), "raw label")
+            self.assertEqual(common.get_raw(common.APP_DEVICE, 0x04, True), b"raw label")
+
+            with self.assertRaises(ValueError):
+                common.set_raw(common.APP_DEVICE, 0x04, bytes(16385), True)
+
+    @mock_storage
+    def test_shared_validators(self):
+        # set_many() validates with the same checks as the individual setters
+        for name, value, setter in (
+            ("rotation", 45, device.set_rotation),
+            ("rotation", 360, device.set_rotation),
+            ("label", "x" * (device.LABEL_MAXLENGTH + 1), device.set_label),
+        ):
+            with self.assertRaises(ValueError):
+                setter(value)
+            with self.assertRaises(ValueError):
+                device.set_many({name: value})
+        self.assertEqual(device.get_rotation(), 0)
+        self.assertIsNone(device.get_label())
+
+    @mock_storage
+    def test_reset_flags(self):
+        device.set_flags(0x0001)
+        device.reset_flags()
+        self.assertEqual(device.get_flags(), 0)
+        self.assertIsNone(common.get(common.APP_DEVICE, 0x08))  # _FLAGS
+
+        device.set_flags(0x0002)
+        self.assertEqual(device.get_flags(), 0x0002)
+
+    @mock_storage
+    def test_peek_device_id(self):
+        self.assertIsNone(device.peek_device_id())
+        # peeking does not generate an ID
+        self.assertIsNone(device.peek_device_id())
+
+        device_id = device.get_device_id()
+        self.assertEqual(len(device_id), 24)
+        self.assertEqual(device.peek_device_id(), device_id)
+
+    @mock_storage
+    def test_rotation(self):
+        self.assertEqual(device.get_rotation(), 0)
+        for rotation in (0, 90, 180, 270):
+            device.set_rotation(rotation)
+            self.assertEqual(device.get_rotation(), rotation)
+
+    @mock_storage
+    def test_autolock_delay_maximum(self):
+        device.set_autolock_delay_ms(120_000)
+        self.assertEqual(device.get_autolock_delay_ms(), 120_000)
+        device.set_autolock_delay_ms(device.AUTOLOCK_DELAY_MAXIMUM)
+        self.assertEqual(device.get_autolock_delay_ms(), device.AUTOLOCK_DELAY_MAXIMUM)
+
+        device.set_autolock_delay_ms(120_000)
+        with self.assertRaises(ValueError):
+            device.set_autolock_delay_ms(device.AUTOLOCK_DELAY_MAXIMUM + 1)
+        self.assertEqual(device.get_autolock_delay_ms(), 120_000)
+
+        device.set_autolock_delay_ms(device.AUTOLOCK_DELAY_MAXIMUM + 1, clamp=True)
+        self.assertEqual(device.get_autolock_delay_ms(), device.AUTOLOCK_DELAY_MAXIMUM)
+
+    @mock_storage
+    def test_has_sd_salt_auth_key(self):
+        self.assertFalse(device.has_sd_salt_auth_key())
+
+        device.set_sd_salt_auth_key(b"\x01" * device.SD_SALT_AUTH_KEY_LEN_BYTES)
+        self.assertTrue(device.has_sd_salt_auth_key())
+        device.set_sd_salt_auth_key(b"")
+        self.assertFalse(device.has_sd_salt_auth_key())
+        self.assertIsNone(device.get_sd_salt_auth_key())
+
+        device.set_sd_salt_auth_key(b"\x01" * device.SD_SALT_AUTH_KEY_LEN_BYTES)
+        device.set_sd_salt_auth_key(None)
+        self.assertFalse(device.has_sd_salt_auth_key())
+
+    @mock_storage
+    def test_set_sd_salt_auth_key_length(self):
+        key = b"\x01" * device.SD_SALT_AUTH_KEY_LEN_BYTES
+        device.set_sd_salt_auth_key(key)
+        self.assertEqual(device.get_sd_salt_auth_key(), key)
+
+        for invalid in (key[:-1], key + b"\x01"):
+            with self.assertRaises(ValueError):
+                device.set_sd_salt_auth_key(invalid)
+            self.assertEqual(device.get_sd_salt_auth_key(), key)
+
+        device.set_sd_salt_auth_key(b"")
+        self.assertIsNone(device.get_sd_salt_auth_key())
+
+    @mock_storage
+    def test_require_initialized(self):
+        device.set_slip39_identifier(42)
+        device.set_slip39_iteration_exponent(1)
+        device.set_slip39_group_threshold(1)
+        device.set_slip39_group_count(2)
+        getters = (
+            device.get_slip39_identifier,
+            device.get_slip39_iteration_exponent,
+            device.get_slip39_group_threshold,
+            device.get_slip39_group_count,
+        )
+
+        with self.assertRaises(RuntimeError):
+            device.require_initialized()
+        for getter in getters:
+            with self.assertRaises(RuntimeError):
+                getter(require_init=True)
+            self.assertIsNotNone(getter())
+
+        device.store_mnemonic_secret(SLIP39_SECRET, BackupType.Slip39_Advanced)
+        device.finalize_initialization()
+        device.require_initialized()
+        self.assertEqual(device.get_slip39_identifier(require_init=True), 42)
+        self.assertEqual(device.get_slip39_iteration_exponent(require_init=True), 1)
+        self.assertEqual(device.get_slip39_group_threshold(require_init=True), 1)
+        self.assertEqual(device.get_slip39_group_count(require_init=True), 2)
+
+    @mock_storage
+    def test_passphrase_always_on_device_disabled(self):
+        device.set_passphrase_enabled(True)
+        device.set_passphrase_always_on_device(True)
+        self.assertTrue(device.get_passphrase_always_on_device())
+
+        # stale value left behind while passphrase is disabled
+        common.set_bool(common.APP_DEVICE, 0x05, False)  # _USE_PASSPHRASE
+        self.assertEqual(device.get_passphrase_source(), 1)
+        self.assertFalse(device.get_passphrase_always_on_device())
+
+        device.set_passphrase_enabled(True)
+        self.assertTrue(device.get_passphrase_always_on_device())
+
+    @mock_storage
+    def test_set_homescreen_format(self):
+        toif = b"TOIf\x90\x00\x90\x00" + bytes(8)
+        device.set_homescreen(toif, format=device.HOMESCREEN_FORMAT_TOIF)
+        self.assertEqual(device.get_homescreen(), toif)
+
+        with self.assertRaises(ValueError):
+            device.set_homescreen(toif, format=device.HOMESCREEN_FORMAT_JPEG)
+        with self.assertRaises(ValueError):
+            device.set_homescreen(b"\xff\xd8\xff\xe0", format=device.HOMESCREEN_FORMAT_TOIF)
+        with self.assertRaises(ValueError):
+            device.set_homescreen(toif, format=42)
+        self.assertEqual(device.get_homescreen(), toif)
+
+        # the unformatted setter does not check anything
+        device.set_homescreen(b"\x01\x02")
+        self.assertEqual(device.get_homescreen(), b"\x01\x02")
+
+    @mock_storage
+    def test_autolock_delay_effective_and_raw(self):
+        self.assertIsNone(device.get_autolock_delay_ms_raw())
+        self.assertEqual(
+            device.get_autolock_delay_ms_effective(), device.AUTOLOCK_DELAY_DEFAULT
+        )
+
+        # _AUTOLOCK_DELAY_MS
+        common.set(common.APP_DEVICE, 0x0C, (1000).to_bytes(4, "big"))
+        self.assertEqual(device.get_autolock_delay_ms_raw(), 1000)
+        self.assertEqual(
+            device.get_autolock_delay_ms_effective(), device.AUTOLOCK_DELAY_MINIMUM
+        )
+        self.assertEqual(device.get_autolock_delay_ms(), device.AUTOLOCK_DELAY_MINIMUM)
+
+    @mock_storage
+    def test_experimental_features_cascade(self):
+        # pretend the label is an experimental-only field
+        with patch(device, "_EXPERIMENTAL_ONLY_FIELDS", ((0x04, True),)):  # _LABEL
+            device.set_experimental_features(True)
+            device.set_label("My Trezor")
+            device.set_rotation(90)
+
+            device.set_experimental_features(True)
+            self.assertEqual(device.get_label(), "My Trezor")
+
+            device.set_experimental_features(False)
+            self.assertFalse(device.get_experimental_features())
+            self.assertIsNone(device.get_label())
+            self.assertEqual(device.get_rotation(), 90)
+
+    @mock_storage
+    def test_get_version_int(self):
+        self.assertIsNone(device.get_version_int())
+        device.set_version(common.STORAGE_VERSION_CURRENT)
+        self.assertEqual(device.get_version_int(), 2)
+
+        common.set(common.APP_DEVICE, 0x01, b"\x02\x00")  # _VERSION
+        with self.assertRaises(ValueError) as e:
+            device.get_version_int()
+        self.assertEqual(e.value.args[0], "Version must be a single byte")
+
+    @mock_storage
+    def test_set_version_unknown(self):
+        device.set_version(b"\x02")
+        self.assertEqual(device.get_version(), b"\x02")
+        device.set_version(b"\x01")
+        self.assertEqual(device.get_version(), b"\x01")
+
+        for invalid in (b"\xff", b"", b"\x01\x02"):
+            with self.assertRaises(ValueError):
+                device.set_version(invalid)
+        self.assertEqual(device.get_version(), b"\x01")
+        with self.assertRaises(ValueError) as e:
+            device.set_version(b"\xff")
+        self.assertEqual(e.value.args[0], "Unknown storage version")
+
+        device.set_version(b"\xff", force=True)
+        self.assertEqual(device.get_version(), b"\xff")
+
+    @mock_storage
+    def test_set_version_length(self):
+        device.set_version(b"\x02", force=True)
+        for invalid in (b"", b"\x02\x00"):
+            with self.assertRaises(ValueError) as e:
+                device.set_version(invalid, force=True)
+            self.assertEqual(e.value.args[0], "Version must be a single byte")
+        self.assertEqual(device.get_version(), b"\x02")
+
+    @mock_storage
+    def test_exact_length_error(self):
+        with self.assertRaises(ValueError) as e:
+            device.set_device_id("0123")
+        self.assertEqual(str(e.value), "Invalid DEVICE_ID length, expected 24")
+
+        with self.assertRaises(ValueError) as e:
+            device.set_sd_salt_auth_key(b"\x01" * 15)
+        self.assertEqual(str(e.value), "Invalid SD_SALT_AUTH_KEY length, expected 16")
+
+    @mock_storage
+    def test_get_flags_decoded(self):
+        device.set_flags(0b1001)
+        decoded = device.get_flags_decoded()
+        self.assertEqual(len(decoded), 16)
+        for name, value in decoded.items():
+            self.assertEqual(value, name in ("flag_0", "flag_3"))
+
+    @mock_storage
+    def test_raw_getters(self):
+        self.assertEqual(device.get_rotation(), 0)
+        self.assertIsNone(device.get_rotation_raw())
+        self.assertEqual(device.get_flags(), 0)
+        self.assertIsNone(device.get_flags_raw())
+        self.assertIsNone(device.get_backup_type())
+        self.assertIsNone(device.get_backup_type_raw())
+        self.assertEqual(device.safety_check_level(), device.SAFETY_CHECK_LEVEL_STRICT)
+        self.assertIsNone(device.safety_check_level_raw())
+
+        device.set_rotation(0)
+        self.assertEqual(device.get_rotation_raw(), 0)
+        device.set_flags(0x02)
+        self.assertEqual(device.get_flags_raw(), 0x02)
+        device.store_mnemonic_secret(SLIP39_SECRET, BackupType.Slip39_Basic)
+        self.assertEqual(device.get_backup_type_raw(), BackupType.Slip39_Basic)
+        device.set_safety_check_level(device.SAFETY_CHECK_LEVEL_PROMPT)
+        self.assertEqual(
+            device.safety_check_level_raw(), device.SAFETY_CHECK_LEVEL_PROMPT
+        )
+
+        # _ROTATION, the raw getter does not validate
+        common.set(common.APP_DEVICE, 0x0F, (45).to_bytes(2, "big"), True)
+        self.assertEqual(device.get_rotation_raw(), 45)
+        self.assertEqual(device.get_rotation(), 0)
+
+    @mock_storage
+    def test_set_homescreen_empty(self):
+        device.set_homescreen(b"\x01" * 10)
+        self.assertEqual(device.get_homescreen(), b"\x01" * 10)
+        device.set_homescreen(b"")
+        self.assertIsNone(device.get_homescreen())
+        self.assertFalse(device.has_homescreen())
+
+    @mock_storage
+    def test_label_color(self):
+        self.assertIsNone(device.get_label_color())
+        for color in (0x00_0000, 0x12_3456, 0xFF_FFFF):
+            device.set_label_color(color)
+            self.assertEqual(device.get_label_color(), color)
+
+        for invalid in (0x100_0000, -1):
+            with self.assertRaises(ValueError):
+                device.set_label_color(invalid)
+        self.assertEqual(device.get_label_color(), 0xFF_FFFF)
+
+    @mock_storage
+    def test_brightness(self):
+        self.assertEqual(device.get_brightness(), device.BRIGHTNESS_DEFAULT)
+        self.assertIsNone(device.get_brightness_raw())
+        for brightness in (0, 128, 255):
+            device.set_brightness(brightness)
+            self.assertEqual(device.get_brightness(), brightness)
+
+        for invalid in (256, -1):
+            with self.assertRaises(ValueError):
+                device.set_brightness(invalid)
+        self.assertEqual(device.get_brightness(), 255)
+
+    @mock_storage
+    def test_is_fresh_device(self):
+        self.assertTrue(device.is_fresh_device())
+        device.set_version(common.STORAGE_VERSION_CURRENT)
+        self.assertFalse(device.is_fresh_device())
+        self.assertFalse(device.is_initialized())
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39)
+        self.assertFalse(device.is_fresh_device())
+
+    @mock_storage
+    def test_homescreen_maxsize(self):
+        self.assertEqual(device.get_homescreen_maxsize(), device.HOMESCREEN_MAXSIZE)
+        device.set_homescreen(b"\x01" * device.HOMESCREEN_MAXSIZE)
+        with self.assertRaises(ValueError):
+            device.set_homescreen(b"\x01" * (device.HOMESCREEN_MAXSIZE + 1))
+
+        device.set_homescreen_maxsize(1024)
+        try:
+            device.set_homescreen(b"\x02" * 1024)
+            with self.assertRaises(ValueError):
+                device.set_homescreen(b"\x03" * 1025)
+            self.assertEqual(device.get_homescreen(), b"\x02" * 1024)
+
+            with self.assertRaises(ValueError):
+                device.set_homescreen_maxsize(device.HOMESCREEN_MAXSIZE + 1)
+            with self.assertRaises(ValueError):
+                device.set_homescreen_maxsize(0)
+            self.assertEqual(device.get_homescreen_maxsize(), 1024)
+            self.assertEqual(device.describe_field("HOMESCREEN")["max_size"], 1024)
+        finally:
+            device.set_homescreen_maxsize(device.HOMESCREEN_MAXSIZE)
+
+    def test_has_wipe_code(self):
+        for configured in (True, False):
+            with patch(config, "has_wipe_code", lambda: configured):
+                self.assertEqual(device.has_wipe_code(), configured)
+
+    @mock_storage
+    def test_validate_device_state(self):
+        self.assertEqual(device.validate_device_state(), [])
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39, needs_backup=True)
+        device.finalize_initialization()
+        self.assertEqual(device.validate_device_state(), [])
+
+        common.delete(common.APP_DEVICE, 0x01)  # _VERSION
+        common.set_bool(common.APP_DEVICE, 0x0A, True)  # _PASSPHRASE_ALWAYS_ON_DEVICE
+        common.set_uint8(common.APP_DEVICE, 0x0E, 7)  # _BACKUP_TYPE
+        common.set_bool(common.APP_DEVICE, 0x0D, True)  # _NO_BACKUP
+        self.assertEqual(
+            device.validate_device_state(),
+            [
+                "initialized without a storage version",
+                "passphrase always on device while passphrase is disabled",
+                "unknown backup type 7",
+                "needs backup while backup is disabled",
+            ],
+        )
+
+    @mock_storage
+    def test_get_homescreen_into(self):
+        buffer = bytearray(8)
+        self.assertEqual(device.get_homescreen_into(buffer), 0)
+
+        device.set_homescreen(b"\x01\x02\x03\x04\x05\x06\x07\x08")
+        self.assertEqual(device.get_homescreen_into(buffer), 8)
+        self.assertEqual(buffer, b"\x01\x02\x03\x04\x05\x06\x07\x08")
+
+        with self.assertRaises(ValueError):
+            device.get_homescreen_into(bytearray(7))
+
+    @mock_storage
+    def test_rotation_observer(self):
+        calls = []
+        device.set_rotation_observer(calls.append)
+        try:
+            device.set_rotation(90)
+            self.assertEqual(calls, [90])
+            device.set_rotation(90)
+            self.assertEqual(calls, [90])
+            with self.assertRaises(ValueError):
+                device.set_rotation(45)
+            self.assertEqual(calls, [90])
+            device.set_rotation(0)
+            self.assertEqual(calls, [90, 0])
+        finally:
+            device.set_rotation_observer(None)
+
+    def test_set_if_changed(self):
+        with MockStorage() as storage:
+            device.set_label("My Trezor")
+            device.set_rotation(90)
+            device.set_autolock_delay_ms(120_000)
+            device.set_passphrase_enabled(True)
+            device.set_slip39_iteration_exponent(1)
+            device.set_homescreen(b"TOIf" + bytes(1024))
+            writes = storage.writes
+
+            # unchanged values are not written again
+            device.set_label("My Trezor")
+            device.set_rotation(90)
+            device.set_autolock_delay_ms(120_000)
+            device.set_passphrase_enabled(True)
+            device.set_slip39_iteration_exponent(1)
+            device.set_homescreen(b"TOIf" + bytes(1024))
+            self.assertEqual(storage.writes, writes)
+
+            device.set_label("Other")
+            self.assertEqual(storage.writes, writes + 1)
+            self.assertEqual(device.get_label(), "Other")
+
+    @mock_storage
+    def test_get_device_id_bytes(self):
+        device.set_device_id("0123456789ABCDEF01234567")
+        self.assertEqual(
+            device.get_device_id_bytes(), unhexlify("0123456789ABCDEF01234567")
+        )
+
+        for corrupt in (b"0123456789ABCDEF0123456Z", b"0123"):
+            common.set(common.APP_DEVICE, 0x00, corrupt, True)  # DEVICE_ID
+            with self.assertRaises(ValueError):
+                device.get_device_id_bytes()
+
+    def test_list_fields(self):
+        fields = device.list_fields()
+        self.assertEqual(len(fields), len(device._FIELDS))
+        self.assertEqual(
+            fields[0],
+            {
+                "name": "DEVICE_ID",
+                "app": common.APP_DEVICE,
+                "key": 0x00,
+                "type": "str",
+                "public": True,
+                "max_size": 24,
+            },
+        )
+        label = [field for field in fields if field["name"] == "LABEL"][0]
+        self.assertEqual(label["key"], 0x04)
+        self.assertEqual(label["type"], "str")
+        self.assertTrue(label["public"])
+        self.assertEqual(label["max_size"], device.LABEL_MAXLENGTH)
+
+    @mock_storage
+    def test_safety_check_level_source(self):
+        self.assertEqual(
+            device.get_safety_check_level_source(), device.SAFETY_CHECK_SOURCE_UNSET
+        )
+        device.set_safety_check_level(
+            device.SAFETY_CHECK_LEVEL_PROMPT, device.SAFETY_CHECK_SOURCE_HOST
+        )
+        self.assertEqual(device.safety_check_level(), device.SAFETY_CHECK_LEVEL_PROMPT)
+        self.assertEqual(
+            device.get_safety_check_level_source(), device.SAFETY_CHECK_SOURCE_HOST
+        )
+        # without a source, the recorded one is kept
+        device.set_safety_check_level(device.SAFETY_CHECK_LEVEL_STRICT)
+        self.assertEqual(device.safety_check_level(), device.SAFETY_CHECK_LEVEL_STRICT)
+        self.assertEqual(
+            device.get_safety_check_level_source(), device.SAFETY_CHECK_SOURCE_HOST
+        )
+        with self.assertRaises(ValueError):
+            device.set_safety_check_level(device.SAFETY_CHECK_LEVEL_PROMPT, 7)
+        self.assertEqual(device.safety_check_level(), device.SAFETY_CHECK_LEVEL_STRICT)
+
+    def test_storage_salt(self):
+        with MockStorage() as storage:
+            salt = device.get_storage_salt()
+            self.assertEqual(len(salt), 32)
+            self.assertEqual(device.get_storage_salt(), salt)
+            self.assertEqual(storage.writes, 1)
+            self.assertEqual(common.get(common.APP_DEVICE, 0x1C), salt)  # _STORAGE_SALT
+
+    @mock_storage
+    def test_finalize_initialization(self):
+        with self.assertRaises(RuntimeError):
+            device.finalize_initialization()
+        device.set_version(common.STORAGE_VERSION_CURRENT)
+        with self.assertRaises(RuntimeError):
+            device.finalize_initialization()
+        self.assertFalse(device.is_initialized())
+
+        # storing the secret does not mark the device as initialized by itself
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39)
+        self.assertFalse(device.is_initialized())
+        device.finalize_initialization()
+        self.assertTrue(device.is_initialized())
+        self.assertEqual(device.validate_device_state(), [])
+
+    @mock_storage
+    def test_finalize_initialization_without_version(self):
+        common.set(common.APP_DEVICE, 0x02, b"secret")  # _MNEMONIC_SECRET
+        with self.assertRaises(RuntimeError):
+            device.finalize_initialization()
+        self.assertFalse(device.is_initialized())
+
+    @mock_storage
+    def test_snapshot(self):
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39, needs_backup=True)
+        device.finalize_initialization()
+        device.set_label("my trezor")
+        device.set_rotation(90)
+        device.set_passphrase_enabled(True)
+        device.set_autolock_delay_ms(60 * 1000)
+        device.set_flags(0x0101)
+        device.get_device_id()
+
+        snapshot = device.snapshot()
+        self.assertTrue(snapshot["initialized"])
+        self.assertEqual(snapshot["device_id"], device.get_device_id())
+        self.assertEqual(snapshot["label"], device.get_label())
+        self.assertEqual(snapshot["rotation"], device.get_rotation())
+        self.assertEqual(snapshot["brightness"], device.get_brightness())
+        self.assertEqual(snapshot["passphrase_enabled"], device.is_passphrase_enabled())
+        self.assertEqual(
+            snapshot["passphrase_always_on_device"],
+            device.get_passphrase_always_on_device(),
+        )
+        self.assertEqual(snapshot["autolock_delay_ms"], device.get_autolock_delay_ms())
+        self.assertEqual(snapshot["safety_check_level"], device.safety_check_level())
+        self.assertEqual(
+            snapshot["experimental_features"], device.get_experimental_features()
+        )
+        self.assertEqual(snapshot["flags"], device.get_flags())
+        for key, value in device.get_backup_state().items():
+            self.assertEqual(snapshot[key], value)
+
+    @mock_storage
+    def test_set_label_whitespace(self):
+        device.set_label("My Trezor")
+        self.assertEqual(device.get_label(), "My Trezor")
+        device.set_label("  My  Trezor \t")
+        self.assertEqual(device.get_label(), "My  Trezor")
+        for invalid in ("My\nTrezor", "My\x00Trezor", "My\x7fTrezor"):
+            with self.assertRaises(ValueError):
+                device.set_label(invalid)
+        self.assertEqual(device.get_label(), "My  Trezor")
+        device.set_label("   ")
+        self.assertIsNone(device.get_label())
+
+    @mock_storage
+    def test_autolock_delay_clamped(self):
+        low = 2 * device.AUTOLOCK_DELAY_MINIMUM
+        high = 4 * device.AUTOLOCK_DELAY_MINIMUM
+        for stored, expected in (
+            (device.AUTOLOCK_DELAY_MINIMUM, low),
+            (3 * device.AUTOLOCK_DELAY_MINIMUM, 3 * device.AUTOLOCK_DELAY_MINIMUM),
+            (5 * device.AUTOLOCK_DELAY_MINIMUM, high),
+        ):
+            device.set_autolock_delay_ms(stored)
+            self.assertEqual(device.get_autolock_delay_ms_clamped(low, high), expected)
+        self.assertEqual(
+            device.get_autolock_delay_ms(), 5 * device.AUTOLOCK_DELAY_MINIMUM
+        )
+
+        # the custom range is intersected with the hardware limits
+        device.set_autolock_delay_ms(device.AUTOLOCK_DELAY_MINIMUM)
+        self.assertEqual(
+            device.get_autolock_delay_ms_clamped(0, high), device.AUTOLOCK_DELAY_MINIMUM
+        )
+        with self.assertRaises(ValueError):
+            device.get_autolock_delay_ms_clamped(high, low)
+        with self.assertRaises(ValueError):
+            device.get_autolock_delay_ms_clamped(0, device.AUTOLOCK_DELAY_MINIMUM - 1)
+
+    @mock_storage
+    def test_backup_type_uninitialized(self):
+        self.assertIsNone(device.get_backup_type())
+        # a stale backup type without a mnemonic secret is ignored
+        common.set(common.APP_DEVICE, 0x0E, b"\x01")  # _BACKUP_TYPE
+        self.assertIsNone(device.get_backup_type())
+
+        device.store_mnemonic_secret(SLIP39_SECRET, BackupType.Slip39_Advanced)
+        self.assertEqual(device.get_backup_type(), BackupType.Slip39_Advanced)
+        common.delete(common.APP_DEVICE, 0x0E)  # _BACKUP_TYPE
+        self.assertEqual(device.get_backup_type(), BackupType.Bip39)
+
+    @mock_storage
+    def test_integer_byte_order(self):
+        device.set_rotation(270)
+        # _ROTATION
+        self.assertEqual(common.get(common.APP_DEVICE, 0x0F, True), b"\x01\x0e")
+        device.set_autolock_delay_ms(0x0102_0304)
+        # _AUTOLOCK_DELAY_MS
+        self.assertEqual(common.get(common.APP_DEVICE, 0x0C), b"\x01\x02\x03\x04")
+        device.set_slip39_identifier(0x1234)
+        # _SLIP39_IDENTIFIER
+        self.assertEqual(common.get(common.APP_DEVICE, 0x10), b"\x12\x34")
+
+        # a little-endian value written by other tools would be misread
+        common.set(common.APP_DEVICE, 0x10, b"\x34\x12")
+        self.assertEqual(device.get_slip39_identifier(), 0x3412)
+
+    def test_get_version_single_read(self):
+        with MockStorage() as storage:
+            self.assertIsNone(device.get_version())
+            self.assertEqual(storage.reads, 1)
+            device.set_version(common.STORAGE_VERSION_CURRENT)
+            storage.reads = 0
+            self.assertEqual(device.get_version(), common.STORAGE_VERSION_CURRENT)
+            self.assertEqual(storage.reads, 1)
+
+    @mock_storage
+    def test_mnemonic_secret_length(self):
+        device.store_mnemonic_secret(BIP39_SECRET, BackupType.Bip39)
+        device.store_mnemonic_secret(bytes(32), BackupType.Slip39_Basic)
+        for secret, backup_type in (
+            (b"secret", BackupType.Bip39),
+            (b"x" * 216, BackupType.Bip39),
+            (bytes(15), BackupType.Slip39_Basic),
+            (bytes(17), BackupType.Slip39_Advanced),
+            (bytes(34), BackupType.Slip39_Advanced),
+            (BIP39_SECRET, BackupType.Slip39_Basic),
+        ):
+            with self.assertRaises(ValueError):
+                device.check_mnemonic_secret(secret, backup_type)
+            with self.assertRaises(ValueError):
+                device.store_mnemonic_secret(secret, backup_type)
+        self.assertEqual(device.get_mnemonic_secret(), bytes(32))
+        self.assertEqual(device.get_backup_type(), BackupType.Slip39_Basic)
+
+    @mock_storage
+    def test_set_flags_returns_flags(self):
+        self.assertEqual(device.set_flags(0x01), 0x01)
+        self.assertEqual(device.get_flags(), 0x01)
+        self.assertEqual(device.set_flags(0x0100), 0x0101)
+        self.assertEqual(device.get_flags(), 0x0101)
+        # already set bits leave the value unchanged
+        self.assertEqual(device.set_flags(0x01), 0x0101)
+        self.assertEqual(device.get_flags(), 0x0101)
+
+    @mock_storage
+    def test_label_hash(self):
+        self.assertIsNone(device.get_label_hash())
+        device.set_label("My Trezor")
+        label_hash = device.get_label_hash()
+        self.assertEqual(len(label_hash), 8)
+        self.assertEqual(device.get_label_hash(), label_hash)
+        device.set_label_color(0xFF0000)
+        self.assertEqual(device.get_label_hash(), label_hash)
+
+        device.set_label("My other Trezor")
+        self.assertNotEqual(device.get_label_hash(), label_hash)
+        device.set_label("My Trezor")
+        self.assertEqual(device.get_label_hash(), label_hash)
+        device.set_label("")
+        self.assertIsNone(device.get_label_hash())
+
+    def test_max_length_constants(self):
+        # host tools and the protobuf definitions rely on these values
+        self.assertEqual(device.LABEL_MAXLENGTH, 32)
+        self.assertEqual(device.HOMESCREEN_MAXSIZE, 16384)
+        self.assertEqual(device.describe_field("LABEL")["max_size"], 32)
+        self.assertEqual(device.describe_field("HOMESCREEN")["max_size"], 16384)
+
+    @mock_storage
+    def test_setter_type_errors(self):
+        with self.assertRaises(TypeError) as e:
+            device.set_rotation("90")
+        self.assertEqual(str(e.value), "Invalid ROTATION type, expected int")
+        self.assertIsNone(device.get_rotation_raw())
+
+        for name, setter in (
+            ("AUTOLOCK_DELAY_MS", device.set_autolock_delay_ms),
+            ("BRIGHTNESS", device.set_brightness),
+            ("FLAGS", device.set_flags),
+            ("SLIP39_IDENTIFIER", device.set_slip39_identifier),
+        ):
+            with self.assertRaises(TypeError) as e:
+                setter(b"\x01")
+            self.assertIn(name, str(e.value))
+
+    @mock_storage
+    def test_rotation_locked(self):
+        self.assertFalse(device.is_rotation_locked())
+        device.set_rotation(90)
+        device.set_rotation_locked(True)
+        self.assertTrue(device.is_rotation_locked())
+        with self.assertRaises(RuntimeError) as e:
+            device.set_rotation(180)
+        self.assertEqual(str(e.value), "Rotation locked")
+        with self.assertRaises(RuntimeError):
+            device.set_many({"label": "My Trezor", "rotation": 180})
+        self.assertEqual(device.get_rotation(), 90)
+        self.assertIsNone(device.get_label())
+
+        device.set_rotation_locked(False)
+        self.assertFalse(device.is_rotation_locked())
+        device.set_rotation(180)
+        self.assertEqual(device.get_rotation(), 180)
+
+    @mock_storage
+    def test_backup_type_name(self):
+        self.assertIsNone(device.get_backup_type_name())
+        for secret, backup_type, name in (
+            (BIP39_SECRET, BackupType.Bip39, "Bip39"),
+            (SLIP39_SECRET, BackupType.Slip39_Basic, "Slip39Basic"),
+            (SLIP39_SECRET, BackupType.Slip39_Advanced, "Slip39Advanced"),
+        ):
+            device.store_mnemonic_secret(secret, backup_type)
+            self.assertEqual(device.get_backup_type_name(), name)
+
+    def test_get_device_id_write_retry(self):
+        with MockStorage() as storage:
+            failures = [1]
+
+            def flaky_set(app, key, data, public=False):
+                if failures[0]:
+                    failures[0] -= 1
+                    raise RuntimeError("Could not save value")
+                storage.set(app, key, data, public)
+
+            with patch(common, "set", flaky_set):
+                device_id = device.get_device_id()
+            self.assertEqual(storage.writes, 1)
+            self.assertEqual(device.peek_device_id(), device_id)
+            self.assertEqual(device.get_device_id(), device_id)
+
+            # persistent failure is reported instead of an unsaved ID
+            common.delete(common.APP_DEVICE, device.DEVICE_ID, public=True)
+            failures[0] = 3
+            with patch(common, "set", flaky_set):
+                with self.assertRaises(RuntimeError):
+                    device.get_device_id()
+            self.assertIsNone(device.peek_device_id())
+
+    def test_field_keys_pinned(self):
+        # the keys are part of the on-flash format and must never be renumbered
+        expected = {
+            "DEVICE_ID": 0x00,
+            "VERSION": 0x01,
+            "MNEMONIC_SECRET": 0x02,
+            "LANGUAGE": 0x03,
+            "LABEL": 0x04,
+            "USE_PASSPHRASE": 0x05,
+            "HOMESCREEN": 0x06,
+            "NEEDS_BACKUP": 0x07,
+            "FLAGS": 0x08,
+            "U2F_COUNTER": 0x09,
+            "PASSPHRASE_ALWAYS_ON_DEVICE": 0x0A,
+            "UNFINISHED_BACKUP": 0x0B,
+            "AUTOLOCK_DELAY_MS": 0x0C,
+            "NO_BACKUP": 0x0D,
+            "BACKUP_TYPE": 0x0E,
+            "ROTATION": 0x0F,
+            "SLIP39_IDENTIFIER": 0x10,
+            "SLIP39_ITERATION_EXPONENT": 0x11,
+            "SD_SALT_AUTH_KEY": 0x12,
+            "INITIALIZED": 0x13,
+            "SAFETY_CHECK_LEVEL": 0x14,
+            "EXPERIMENTAL_FEATURES": 0x15,
+            "SLIP39_GROUP_THRESHOLD": 0x16,
+            "SLIP39_GROUP_COUNT": 0x17,
+            "LABEL_COLOR": 0x18,
+            "BRIGHTNESS": 0x19,
+            "SAFETY_CHECK_LEVEL_SOURCE": 0x1B,
+            "STORAGE_SALT": 0x1C,
+            "ROTATION_LOCKED": 0x1D,
+        }
+        self.assertEqual(len(expected), len(device._FIELDS))
+        for name, key in expected.items():
+            self.assertEqual(device.describe_field(name)["key"], key)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,6 +1,7 @@
 from common import *
 from trezor import config
-from storage import device
+import storage
+from storage import common, device
 
 
 class TestConfig(unittest.TestCase):
@@ -13,9 +14,117 @@ class TestConfig(unittest.TestCase):
         device.set_u2f_counter(350)
         for i in range(351, 500):
             self.assertEqual(device.next_u2f_counter(), i)
-        device.set_u2f_counter(0)
+        device.set_u2f_counter(0, force=True)
         self.assertEqual(device.next_u2f_counter(), 1)
 
+    def test_counter_monotonic(self):
+        config.init()
+        config.wipe()
+        self.assertEqual(device.get_u2f_counter(), None)
+        device.set_u2f_counter(100)
+        self.assertEqual(device.get_u2f_counter(), 100)
+        self.assertEqual(device.next_u2f_counter(), 101)
+        self.assertEqual(device.get_u2f_counter(), 101)
+
+        # increasing and equal values are accepted
+        device.set_u2f_counter(200)
+        device.set_u2f_counter(200)
+        self.assertEqual(device.get_u2f_counter(), 200)
+
+        # decreasing is rejected unless forced
+        with self.assertRaises(ValueError):
+            device.set_u2f_counter(150)
+        self.assertEqual(device.get_u2f_counter(), 200)
+        device.set_u2f_counter(150, force=True)
+        self.assertEqual(device.get_u2f_counter(), 150)
+
+    def test_counter_unique(self):
+        config.init()
+        config.wipe()
+        first = device.next_u2f_counter()
+        second = device.next_u2f_counter()
+        self.assertEqual(second, first + 1)
+        values = [device.next_u2f_counter() for _ in range(100)]
+        self.assertEqual(values, list(range(second + 1, second + 101)))
+
+    def test_migrate_dry_run(self):
+        config.init()
+        config.wipe()
+        self.assertEqual(config.unlock('', None), True)
+        self.assertEqual(storage.migrate_dry_run(), {"version": None, "changes": []})
+
+        # simulate a storage written by FW <= 2.3.1
+        common.set(common.APP_DEVICE, 0x01, common.STORAGE_VERSION_01)  # _VERSION
+        common.set(common.APP_DEVICE, device.U2F_COUNTER, (5).to_bytes(4, "big"))
+        report = storage.migrate_dry_run()
+        self.assertEqual(report["version"], common.STORAGE_VERSION_01)
+        self.assertEqual(report["changes"], [
+            "U2F_COUNTER: move 5 to the public counter",
+            "VERSION: 1 -> 2",
+            "INITIALIZED: set",
+        ])
+        # nothing was changed
+        self.assertEqual(device.get_version(), common.STORAGE_VERSION_01)
+        self.assertFalse(device.is_initialized())
+
+        storage.init_unlocked()
+        self.assertEqual(storage.migrate_dry_run()["changes"], [])
+        self.assertEqual(device.get_u2f_counter(), 5)
+
+    def test_storage_stats(self):
+        config.init()
+        config.wipe()
+        self.assertEqual(config.unlock('', None), True)
+        stats = storage.get_storage_stats()
+        self.assertEqual(sorted(stats.keys()), ['available', 'used'])
+
+        device.set_homescreen(b'\x00' * 8192)
+        new_stats = storage.get_storage_stats()
+        self.assertTrue(new_stats['used'] >= stats['used'] + 8192)
+        self.assertTrue(new_stats['available'] <= stats['available'] - 8192)
+
+    def test_read_cache(self):
+        config.init()
+        config.wipe()
+        self.assertEqual(config.unlock('', None), True)
+        common.enable_cache(True)
+        try:
+            self.assertEqual(device.get_label(), None)
+            # a write which bypasses the storage module is not visible until the
+            # cache is invalidated
+            config.set(common.APP_DEVICE, 0x04, b'external', True)  # _LABEL
+            self.assertEqual(device.get_label(), None)
+            common.invalidate_cache()
+            self.assertEqual(device.get_label(), 'external')
+            # writes through the storage module invalidate the cache
+            device.set_label('mytrezor')
+            self.assertEqual(device.get_label(), 'mytrezor')
+            device.set_flags(0x04)
+            self.assertEqual(device.get_flags(), 0x04)
+            # private values are always read from the storage
+            config.set(common.APP_DEVICE, 0x08, b'\x00\x00\x00\x05')  # _FLAGS
+            self.assertEqual(device.get_flags(), 0x05)
+        finally:
+            common.enable_cache(False)
+
+    def test_storage_generation(self):
+        config.init()
+        config.wipe()
+        self.assertEqual(config.unlock('', None), True)
+        generation = common.get_storage_generation()
+        device.get_label()
+        device.get_flags()
+        self.assertEqual(common.get_storage_generation(), generation)
+
+        device.set_label('mytrezor')
+        self.assertTrue(common.get_storage_generation() > generation)
+        generation = common.get_storage_generation()
+        device.set_label('')
+        self.assertTrue(common.get_storage_generation() > generation)
+        generation = common.get_storage_generation()
+        device.next_u2f_counter()
+        self.assertTrue(common.get_storage_generation() > generation)
+
 
 if __name__ == '__main__':
     unittest.main()
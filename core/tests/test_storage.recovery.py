@@ -0,0 +1,89 @@
+from common import *
+from mock_storage import mock_storage
+
+from storage import device, recovery, recovery_shares
+from trezor.enums import BackupType
+
+# Two shares of the same basic SLIP-39 backup, see slip39_vectors.py
+SHARES = [
+    (2, "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed"),
+    (0, "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking"),
+]
+IDENTIFIER = 25653
+ITERATION_EXPONENT = 2
+
+
+class TestStorageRecovery(unittest.TestCase):
+    def _start_recovery(self, identifier):
+        recovery.set_in_progress(True)
+        recovery.set_slip39_group_count(1)
+        recovery.set_slip39_identifier(identifier)
+        recovery.set_slip39_iteration_exponent(ITERATION_EXPONENT)
+        for index, mnemonic in SHARES:
+            recovery_shares.set(index, 0, mnemonic)
+
+    @mock_storage
+    def test_verify_before_commit(self):
+        self._start_recovery(IDENTIFIER)
+        recovery.verify_before_commit()
+
+    @mock_storage
+    def test_verify_before_commit_inconsistent(self):
+        self._start_recovery(IDENTIFIER + 1)
+        with self.assertRaises(ValueError):
+            recovery.verify_before_commit()
+        self.assertEqual(len(recovery_shares.fetch_group(0)), len(SHARES))
+
+    @mock_storage
+    def test_start_and_end_progress(self):
+        self.assertFalse(recovery.is_in_progress())
+        self._start_recovery(IDENTIFIER)
+        recovery.set_dry_run(True)
+        self.assertTrue(recovery.is_in_progress())
+        self.assertTrue(recovery.is_dry_run())
+        self.assertEqual(recovery.get_slip39_identifier(), IDENTIFIER)
+
+        recovery.end_progress()
+        self.assertFalse(recovery.is_in_progress())
+        with self.assertRaises(RuntimeError):
+            recovery.get_slip39_identifier()
+        with self.assertRaises(RuntimeError):
+            recovery.end_progress()
+
+        # a new recovery starts from scratch
+        recovery.set_in_progress(True)
+        self.assertFalse(recovery.is_dry_run())
+        self.assertIsNone(recovery.get_slip39_identifier())
+        self.assertIsNone(recovery.get_slip39_iteration_exponent())
+        self.assertEqual(recovery_shares.fetch_group(0), [])
+
+    @mock_storage
+    def test_abort_recovery(self):
+        self._start_recovery(IDENTIFIER)
+        recovery.set_dry_run(True)
+        recovery.set_slip39_remaining_shares(1, 0)
+        device.store_mnemonic_secret(bytes(16), BackupType.Slip39_Basic)
+        device.set_slip39_identifier(IDENTIFIER)
+        device.finalize_initialization()
+
+        recovery.abort_recovery()
+        self.assertFalse(recovery.is_in_progress())
+        self.assertEqual(recovery_shares.total_share_count(), 0)
+        recovery.set_in_progress(True)
+        self.assertFalse(recovery.is_dry_run())
+        self.assertIsNone(recovery.get_slip39_identifier())
+        self.assertIsNone(recovery.get_slip39_iteration_exponent())
+        self.assertIsNone(recovery.get_slip39_remaining_shares(0))
+        self.assertIsNone(recovery.fetch_slip39_remaining_shares())
+
+        # the finalized device is left alone
+        self.assertEqual(device.get_mnemonic_secret(), bytes(16))
+        self.assertEqual(device.get_slip39_identifier(), IDENTIFIER)
+        self.assertTrue(device.is_initialized())
+
+        # aborting without a recovery in progress is fine
+        recovery.abort_recovery()
+
+
+if __name__ == "__main__":
+    unittest.main()
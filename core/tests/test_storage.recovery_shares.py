@@ -0,0 +1,125 @@
+from common import *
+from mock_storage import mock_storage
+
+from storage import common, device, recovery_shares
+from trezor.crypto import slip39
+from trezor.enums import BackupType
+
+MNEMONICS = [
+    (2, "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed"),
+    (0, "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking"),
+]
+
+
+class TestStorageRecoveryShares(unittest.TestCase):
+    @mock_storage
+    def test_iter_group(self):
+        for index, mnemonic in MNEMONICS:
+            recovery_shares.set(index, 1, mnemonic)
+
+        it = recovery_shares.iter_group(1)
+        # shares are yielded in order of their index
+        self.assertEqual(next(it), MNEMONICS[1][1])
+        self.assertEqual(next(it), MNEMONICS[0][1])
+        with self.assertRaises(StopIteration):
+            next(it)
+
+        self.assertEqual(list(recovery_shares.iter_group(0)), [])
+        self.assertEqual(
+            list(recovery_shares.iter_group(1)), recovery_shares.fetch_group(1)
+        )
+
+    @mock_storage
+    def test_iter_group_partial(self):
+        for index, mnemonic in MNEMONICS:
+            recovery_shares.set(index, 0, mnemonic)
+
+        for mnemonic in recovery_shares.iter_group(0):
+            self.assertEqual(mnemonic, MNEMONICS[1][1])
+            break
+        self.assertEqual(len(recovery_shares.fetch_group(0)), 2)
+
+    @mock_storage
+    def test_set_length(self):
+        recovery_shares.set(0, 0, "x" * 256)
+        self.assertEqual(recovery_shares.get(0, 0), "x" * 256)
+
+        with self.assertRaises(ValueError):
+            recovery_shares.set(1, 0, "x" * 257)
+        self.assertIsNone(recovery_shares.get(1, 0))
+
+    @mock_storage
+    def test_share_key(self):
+        last = slip39.MAX_SHARE_COUNT - 1
+        last_group = slip39.MAX_GROUP_COUNT - 1
+        recovery_shares.set(last, last_group, MNEMONICS[0][1])
+        self.assertEqual(
+            common.get(common.APP_RECOVERY_SHARES, 0xFF), MNEMONICS[0][1].encode()
+        )
+        self.assertEqual(recovery_shares.get(last, last_group), MNEMONICS[0][1])
+
+        for index, group_index in ((last + 1, 0), (0, last_group + 1), (-1, 0)):
+            with self.assertRaises(ValueError):
+                recovery_shares.set(index, group_index, MNEMONICS[0][1])
+            with self.assertRaises(ValueError):
+                recovery_shares.get(index, group_index)
+
+    @mock_storage
+    def test_delete_group(self):
+        for index, mnemonic in MNEMONICS:
+            recovery_shares.set(index, 0, mnemonic)
+            recovery_shares.set(index, 3, mnemonic)
+
+        recovery_shares.delete_group(3)
+        self.assertEqual(recovery_shares.fetch_group(3), [])
+        self.assertEqual(len(recovery_shares.fetch_group(0)), 2)
+
+        with self.assertRaises(ValueError):
+            recovery_shares.delete_group(slip39.MAX_GROUP_COUNT)
+
+    @mock_storage
+    def test_total_share_count(self):
+        self.assertEqual(recovery_shares.total_share_count(), 0)
+
+        for index, mnemonic in MNEMONICS:
+            recovery_shares.set(index, 0, mnemonic)
+        recovery_shares.set(5, 7, MNEMONICS[0][1])
+        self.assertEqual(recovery_shares.total_share_count(), 3)
+
+        for group_index in range(slip39.MAX_GROUP_COUNT):
+            for index in range(slip39.MAX_SHARE_COUNT):
+                recovery_shares.set(index, group_index, MNEMONICS[0][1])
+        self.assertEqual(
+            recovery_shares.total_share_count(),
+            slip39.MAX_SHARE_COUNT * slip39.MAX_GROUP_COUNT,
+        )
+
+    @mock_storage
+    def test_has(self):
+        recovery_shares.set(2, 1, MNEMONICS[0][1])
+        self.assertTrue(recovery_shares.has(2, 1))
+        self.assertFalse(recovery_shares.has(0, 1))
+        self.assertFalse(recovery_shares.has(2, 0))
+        for index, group_index in ((slip39.MAX_SHARE_COUNT, 0), (0, -1)):
+            with self.assertRaises(ValueError):
+                recovery_shares.has(index, group_index)
+
+    @mock_storage
+    def test_set_initialized(self):
+        index, mnemonic = MNEMONICS[0]
+        recovery_shares.set(index, 0, mnemonic)
+        self.assertEqual(recovery_shares.get(index, 0), mnemonic)
+        recovery_shares.delete()
+
+        device.store_mnemonic_secret(bytes(16), BackupType.Slip39_Basic)
+        device.finalize_initialization()
+        with self.assertRaises(RuntimeError):
+            recovery_shares.set(index, 0, mnemonic)
+        self.assertIsNone(recovery_shares.get(index, 0))
+
+        recovery_shares.set(index, 0, mnemonic, force=True)
+        self.assertEqual(recovery_shares.get(index, 0), mnemonic)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -571,6 +571,14 @@ secbool norcow_update_bytes(const uint16_t key, const uint16_t offset,
   return sectrue;
 }
 
+/*
+ * Returns the number of used and free bytes in the writing sector
+ */
+void norcow_get_usage(uint32_t *used, uint32_t *available) {
+  *used = norcow_free_offset - NORCOW_STORAGE_START;
+  *available = NORCOW_SECTOR_SIZE - norcow_free_offset;
+}
+
 /*
  * Complete storage version upgrade
  */
@@ -78,6 +78,12 @@ secbool norcow_update_word(uint16_t key, uint16_t offset, uint32_t value);
 secbool norcow_update_bytes(const uint16_t key, const uint16_t offset,
                             const uint8_t *data, const uint16_t len);
 
+/*
+ * Returns the number of used and free bytes in the writing sector. Items which
+ * were deleted or overwritten count as used until the sector is compacted.
+ */
+void norcow_get_usage(uint32_t *used, uint32_t *available);
+
 /*
  * Complete storage version upgrade
  */
@@ -144,7 +144,10 @@ const uint8_t WIPE_CODE_EMPTY[] = {0, 0, 0, 0};
 // The uint32 representation of an empty wipe code used in storage version 2.
 #define V2_WIPE_CODE_EMPTY 0
 
-// The length of the counter tail in words.
+// The length of the counter tail in words. A counter is stored as its base
+// value followed by a tail of "1" bits used as a tally. Incrementing clears one
+// bit of the tail in place, which flash allows without erasing, so the item is
+// only rewritten once every 32 * COUNTER_TAIL_WORDS increments.
 #define COUNTER_TAIL_WORDS 2
 
 // Values used in the guard key integrity check.
@@ -1381,6 +1384,56 @@ secbool storage_next_counter(const uint16_t key, uint32_t *count) {
   }
 }
 
+secbool storage_get_counter(const uint16_t key, uint32_t *count) {
+  const uint8_t app = key >> 8;
+  // APP == 0 is reserved for PIN related values
+  if (sectrue != initialized || app == APP_STORAGE ||
+      (app & FLAG_PUBLIC) == 0) {
+    return secfalse;
+  }
+
+  uint16_t len = 0;
+  const uint32_t *val_stored = NULL;
+  if (sectrue != norcow_get(key, (const void **)&val_stored, &len)) {
+    return secfalse;
+  }
+
+  if (len < sizeof(uint32_t) || len % sizeof(uint32_t) != 0) {
+    return secfalse;
+  }
+  uint16_t len_words = len / sizeof(uint32_t);
+
+  uint16_t i = 1;
+  while (i < len_words && val_stored[i] == 0) {
+    ++i;
+  }
+
+  *count = val_stored[0] + 32 * (i - 1);
+  if (*count < val_stored[0]) {
+    // Value overflow.
+    return secfalse;
+  }
+
+  if (i < len_words) {
+    *count += hamming_weight(~val_stored[i]);
+    if (*count < val_stored[0]) {
+      // Value overflow.
+      return secfalse;
+    }
+  }
+  return sectrue;
+}
+
+void storage_get_usage(uint32_t *used, uint32_t *available) {
+  if (sectrue != initialized) {
+    *used = 0;
+    *available = 0;
+    return;
+  }
+
+  norcow_get_usage(used, available);
+}
+
 secbool storage_has_pin(void) {
   if (sectrue != initialized) {
     return secfalse;
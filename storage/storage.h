@@ -71,5 +71,7 @@ secbool storage_set(const uint16_t key, const void *val, const uint16_t len);
 secbool storage_delete(const uint16_t key);
 secbool storage_set_counter(const uint16_t key, const uint32_t count);
 secbool storage_next_counter(const uint16_t key, uint32_t *count);
+secbool storage_get_counter(const uint16_t key, uint32_t *count);
+void storage_get_usage(uint32_t *used, uint32_t *available);
 
 #endif
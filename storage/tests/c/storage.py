@@ -66,6 +66,26 @@ class Storage:
             raise RuntimeError("Failed to get value from storage.")
         return s.raw
 
+    def get_length(self, key: int) -> int:
+        val_len = c.c_uint16()
+        if sectrue != self.lib.storage_get(c.c_uint16(key), None, 0, c.byref(val_len)):
+            raise RuntimeError("Failed to find key in storage.")
+        return val_len.value
+
+    def get_into(self, key: int, buffer: bytearray) -> int:
+        val_len = c.c_uint16()
+        if sectrue != self.lib.storage_get(c.c_uint16(key), None, 0, c.byref(val_len)):
+            raise RuntimeError("Failed to find key in storage.")
+        if val_len.value > len(buffer):
+            raise ValueError("Buffer too small.")
+        if val_len.value > 0:
+            dest = (c.c_char * len(buffer)).from_buffer(buffer)
+            if sectrue != self.lib.storage_get(
+                c.c_uint16(key), dest, val_len, c.byref(val_len)
+            ):
+                raise RuntimeError("Failed to get value from storage.")
+        return val_len.value
+
     def set(self, key: int, val: bytes) -> None:
         if sectrue != self.lib.storage_set(c.c_uint16(key), val, c.c_uint16(len(val))):
             raise RuntimeError("Failed to set value in storage.")
@@ -82,9 +102,21 @@ class Storage:
             raise RuntimeError("Failed to set value in storage.")
         return count.value
 
+    def get_counter(self, key: int) -> int:
+        count = c.c_uint32()
+        if sectrue != self.lib.storage_get_counter(c.c_uint16(key), c.byref(count)):
+            raise RuntimeError("Failed to get value from storage.")
+        return count.value
+
     def delete(self, key: int) -> bool:
         return sectrue == self.lib.storage_delete(c.c_uint16(key))
 
+    def get_usage(self) -> (int, int):
+        used = c.c_uint32()
+        available = c.c_uint32()
+        self.lib.storage_get_usage(c.byref(used), c.byref(available))
+        return used.value, available.value
+
     def _dump(self) -> bytes:
         # return just sectors 4 and 16 of the whole flash
         return [
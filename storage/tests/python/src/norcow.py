@@ -80,6 +80,14 @@ class Norcow:
             )
         self._write(offset, key, new_value)
 
+    def get_usage(self) -> (int, int):
+        """
+        Returns the number of used and free bytes in the active sector. Items
+        which were deleted or overwritten count as used until compaction.
+        """
+        used = self.active_offset - len(consts.NORCOW_MAGIC_AND_VERSION)
+        return used, consts.NORCOW_SECTOR_SIZE - self.active_offset
+
     def _is_updatable(self, old: bytes, new: bytes) -> bool:
         """
         Item is updatable if the new value is the same or
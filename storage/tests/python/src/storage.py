@@ -138,6 +138,16 @@ class Storage:
             raise RuntimeError("Failed to find key in storage.")
         return value
 
+    def get_length(self, key: int) -> int:
+        return len(self.get(key))
+
+    def get_into(self, key: int, buffer: bytearray) -> int:
+        value = self.get(key)
+        if len(value) > len(buffer):
+            raise ValueError("Buffer too small.")
+        buffer[: len(value)] = value
+        return len(value)
+
     def set(self, key: int, val: bytes) -> bool:
         app = key >> 8
         self._check_lock(app)
@@ -185,6 +195,19 @@ class Storage:
         )
         return increased_count
 
+    def get_counter(self, key: int) -> int:
+        app = key >> 8
+        if not self.initialized or not consts.is_app_public(app):
+            raise RuntimeError("Counter can be read only for public items")
+
+        current = self.nc.get(key)
+        if current is False:
+            raise RuntimeError("Failed to find key in storage.")
+
+        base = int.from_bytes(current[:4], sys.byteorder)
+        tail = helpers.to_int_by_words(current[4:])
+        return base + f"{tail:064b}".count("0")
+
     def delete(self, key: int) -> bool:
         app = key >> 8
         self._check_lock(app)
@@ -194,6 +217,11 @@ class Storage:
             self.nc.set(consts.SAT_KEY, sat)
         return ret
 
+    def get_usage(self) -> (int, int):
+        if not self.initialized:
+            return 0, 0
+        return self.nc.get_usage()
+
     def _check_lock(self, app: int):
         if not self.initialized or consts.is_app_private(app):
             raise RuntimeError("Storage not initialized or app is private")
@@ -169,6 +169,15 @@ def test_set_locked():
         s.get(0xC003) == b"test"
 
 
+def test_counter_many_increments():
+    sc, sp = common.init(unlock=True)
+    # cross the end of the tally tail many times
+    for i in range(0, 1000):
+        for s in (sc, sp):
+            assert i == s.next_counter(0xC001)
+    assert common.memory_equals(sc, sp)
+
+
 def test_counter():
     sc, sp = common.init(unlock=True)
     for i in range(0, 200):
@@ -199,3 +208,98 @@ def test_counter():
             s.next_counter(0xC001)
 
     assert common.memory_equals(sc, sp)
+
+
+def test_get_counter():
+    sc, sp = common.init(unlock=True)
+    for s in (sc, sp):
+        with pytest.raises(RuntimeError):
+            s.get_counter(0xC001)
+
+    for i in range(0, 100):
+        for s in (sc, sp):
+            s.next_counter(0xC001)
+            assert s.get_counter(0xC001) == i
+        assert common.memory_equals(sc, sp)
+
+    # reading does not increment the counter and works on a locked device
+    for s in (sc, sp):
+        s.lock()
+        s.set_counter(0xC001, 500)
+        assert s.get_counter(0xC001) == 500
+        assert s.get_counter(0xC001) == 500
+        assert s.next_counter(0xC001) == 501
+        with pytest.raises(RuntimeError):
+            s.get_counter(0x0101)
+    assert common.memory_equals(sc, sp)
+
+
+def test_get_length():
+    values = [(0x0301, b""), (0x0302, b"Hello"), (0x8301, b""), (0x8302, b"world!")]
+    sc, sp = common.init(unlock=True)
+    for s in (sc, sp):
+        for key, val in values:
+            s.set(key, val)
+    assert common.memory_equals(sc, sp)
+
+    for s in (sc, sp):
+        for key, val in values:
+            assert s.get_length(key) == len(val)
+        with pytest.raises(RuntimeError):
+            s.get_length(0xABCD)
+
+    # public values can be measured on a locked device
+    for s in (sc, sp):
+        s.lock()
+        assert s.get_length(0x8302) == len(b"world!")
+        with pytest.raises(RuntimeError):
+            s.get_length(0x0302)
+    assert common.memory_equals(sc, sp)
+
+
+def test_get_into():
+    values = [(0x0301, b""), (0x0302, b"Hello"), (0x8301, b""), (0x8302, b"world!")]
+    sc, sp = common.init(unlock=True)
+    for s in (sc, sp):
+        for key, val in values:
+            s.set(key, val)
+
+    for s in (sc, sp):
+        for key, val in values:
+            exact = bytearray(len(val))
+            assert s.get_into(key, exact) == len(val)
+            assert exact == val
+
+            larger = bytearray(b"\xAA" * (len(val) + 4))
+            assert s.get_into(key, larger) == len(val)
+            assert larger == val + b"\xAA" * 4
+
+        small = bytearray(len(b"Hello") - 1)
+        with pytest.raises(ValueError):
+            s.get_into(0x0302, small)
+        assert small == bytearray(len(small))
+        with pytest.raises(RuntimeError):
+            s.get_into(0xABCD, bytearray(8))
+    assert common.memory_equals(sc, sp)
+
+
+def test_usage():
+    sc, sp = common.init(unlock=True)
+    assert sc.get_usage() == sp.get_usage()
+    used, available = sc.get_usage()
+
+    for s in (sc, sp):
+        s.set(0xBEEF, b"Hello")
+        s.set(0x0301, b"world!")
+    assert sc.get_usage() == sp.get_usage()
+    assert sc.get_usage()[0] > used
+    assert sum(sc.get_usage()) == used + available
+
+    # deleted and overwritten items count as used until compaction
+    used, available = sc.get_usage()
+    for s in (sc, sp):
+        s.set(0xBEEF, b"Hello world")
+        assert s.delete(0x0301)
+    assert sc.get_usage() == sp.get_usage()
+    assert sc.get_usage()[0] > used
+    assert common.memory_equals(sc, sp)